@@ -2,329 +2,1524 @@ use proc_macro::TokenStream;
 use quote::{quote, format_ident};
 use syn::{parse_macro_input, DeriveInput, Data::{self, Struct}, Fields, Ident,
     PathSegment, PathArguments, AngleBracketedGenericArguments, GenericArgument,
-    TypePath, Type, Path, spanned::Spanned,
+    TypePath, Type, Path, Member, spanned::Spanned, ext::IdentExt,
 };
 
 #[proc_macro_derive(Builder, attributes(builder))]
 pub fn derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!{input as DeriveInput};
 
-    let builder_name = format_ident!("{}Builder", &input.ident.to_string());
+    let base_name = &input.ident;
 
-    let base_name = input.ident;
-
-    // derive Builder only on structs for now.
-    assert!(matches!(input.data, Data::Struct {..}));
+    let options = match struct_options(&input.attrs) {
+        Ok(options) => options,
+        Err(e) => return syn::Error::into_compile_error(e).into(),
+    };
+    let typestate = options.typestate;
+    let roots = options.roots();
+    let Roots { core, .. } = &roots;
 
-    let builder_fields_declaration = match &input.data {
+    // a struct gets a single `builder()`, an enum gets a `build_<variant>()`
+    // for every one of its variants.
+    let targets = match &input.data {
         Struct(data_struct) => {
-            match &data_struct.fields {
-                Fields::Named(fields) => {
-                    let recurse_declaration = fields.named.iter().map(|f| {
-                        let name = &f.ident;
-                        let ty = &f.ty;
-                        quote! {
-                            #name : std::option::Option<#ty>,
-                        }
-                    });
+            let builder_name = match &options.builder_name {
+                Some(builder_name) => builder_name.clone(),
+                None => format_ident!("{}Builder", &base_name.to_string()),
+            };
+            builder_fields(&data_struct.fields, &options, None).map(|fields| vec![BuilderTarget::new(
+                builder_name,
+                format_ident!("builder"),
+                quote!(#base_name),
+                fields,
+            )])
+        }
+        Data::Enum(_) if options.builder_name.is_some() => {
+            Err(syn::Error::new(options.builder_name.span(), "builders of an enum are named on its variants"))
+        }
+        Data::Enum(data_enum) => {
+            data_enum.variants.iter().map(|variant| {
+                let variant_name = &variant.ident;
+                let builder_name = match variant_builder_name(&variant.attrs)? {
+                    Some(builder_name) => builder_name,
+                    None => format_ident!("{}{}Builder", &base_name, variant_name),
+                };
+                Ok(BuilderTarget::new(
+                    builder_name,
+                    format_ident!("build_{}", to_snake_case(&variant_name.to_string())),
+                    quote!(#base_name::#variant_name),
+                    builder_fields(&variant.fields, &options, Some(&input.vis))?,
+                ))
+            }).collect()
+        }
+        // a union has no way to tell which of its fields the builder is for.
+        Data::Union(data_union) => {
+            Err(syn::Error::new(data_union.union_token.span, "Builder can only be derived for structs and enums"))
+        }
+    };
+
+    let targets = match targets {
+        Ok(targets) => targets,
+        Err(e) => return syn::Error::into_compile_error(e).into(),
+    };
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let builder_vis = options.builder_vis(&input);
+
+    let builders = targets.iter().map(|target| {
+        if typestate {
+            generate_typestate_builder(target, &input, &options)
+        } else {
+            generate_builder(target, &input, &options)
+        }
+    });
+
+    let constructors = targets.iter().map(|target| {
+        let BuilderTarget { builder_name, constructor, fields, .. } = target;
+
+        // a typestate builder starts out with every required field unset.
+        let builder_type = if typestate {
+            let args = generic_arguments(&input.generics);
+            let unset = typestate_params(fields).into_iter().flatten().map(|_| quote!(()));
+            quote!(#builder_name<#(#args,)* #(#unset),*>)
+        } else {
+            quote!(#builder_name #ty_generics)
+        };
+
+        // collections of `each` fields are created by their first element, and
+        // by `build` when there is none.
+        let builder_fields_definition = fields.iter().map(|f| {
+            let name = &f.ident;
+            if typestate && f.is_required() {
+                quote! {
+                    #name: (),
+                }
+            } else {
+                quote! {
+                     #name : #core::option::Option::None,
+                }
+            }
+        });
 
-                    quote! {#(#recurse_declaration)* }
+        let phantom_definition = phantom_field(&input, &roots).map(|_| quote! {
+            __phantom: #core::marker::PhantomData,
+        });
+
+        quote! {
+            #builder_vis fn #constructor() -> #builder_type {
+                #builder_name {
+                    #(#builder_fields_definition)*
+                    #phantom_definition
                 }
-                _ => unreachable!(),
             }
         }
-        _ => unreachable!(),
+    });
+
+    // only a struct value is sure to fit the builder, a variant builder has
+    // no use for the other variants.
+    let (from_impls, to_builders) = match input.data {
+        Struct(_) => targets.iter().map(|target| generate_to_builder(target, &input, &options)).unzip(),
+        _ => (Vec::new(), Vec::new()),
     };
 
-    let builder_fields_definition_stream = match &input.data {
-        Struct(data_struct) => {
-            match &data_struct.fields {
-                Fields::Named(fields) => {
-                    // part06: if field is vector of something then in definition the option should
-                    // be an empty vector of that thing.
-                    fields.named.iter().map(|f| {
-                        // filter list attributes with path 'builder'
-                        let attrs = f.attrs.iter().filter(|attr| {
-                            if let syn::Meta::List(syn::MetaList {path, ..}) = &attr.meta {
-                                if path.is_ident("builder") {
-                                    true
-                                } else {
-                                    false
-                                }
-                            } else {
-                                false
-                            }
-                        }).collect::<Vec<_>>();
-
-                        let one_by_one_setter = one_by_one_setter(&attrs)?;
-
-                        if one_by_one_setter.is_some() {
-                            let name = &f.ident;
-                            Ok(quote! {
-                                #name: Some(std::vec::Vec::new()),
-                            })
-                        } else {
-                            let name = &f.ident;
-                            Ok(quote! {
-                                 #name : std::option::Option::None,
-                            })
-                        }
+    let output = quote!{
+        #(#builders)*
+        #(#from_impls)*
 
-                    }).collect::<syn::Result<proc_macro2::TokenStream>>()
-                }
-                _ => unreachable!(),
+        impl #impl_generics #base_name #ty_generics #where_clause {
+            #(#constructors)*
+            #(#to_builders)*
+        }
+    };
+
+    TokenStream::from(output)
+}
+
+// `impl From<X> for XBuilder` and `X::to_builder`, giving a builder with every
+// field set from an existing value. The values of skipped fields are dropped,
+// `build` fills them in again.
+fn generate_to_builder(target: &BuilderTarget, input: &DeriveInput, options: &StructOptions) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let roots = options.roots();
+    let Roots { core, .. } = &roots;
+    let BuilderTarget { builder_name, fields, .. } = target;
+    let base_name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // a typestate builder made from a value has all of its required fields
+    // set already.
+    let builder_type = if options.typestate {
+        let args = generic_arguments(&input.generics);
+        let set = fields.iter().filter(|f| f.is_required()).map(|f| f.ty);
+        quote!(#builder_name<#(#args,)* #(#set),*>)
+    } else {
+        quote!(#builder_name #ty_generics)
+    };
+
+    let builder_fields_definition = |value: &dyn Fn(&Member) -> proc_macro2::TokenStream| {
+        let definitions = fields.iter().map(|f| {
+            let name = &f.ident;
+            let value = value(&f.member);
+            if options.typestate && f.is_required() {
+                quote!(#name: #value,)
+            } else {
+                quote!(#name: #core::option::Option::Some(#value),)
+            }
+        });
+        let phantom_definition = phantom_field(input, &roots).map(|_| quote! {
+            __phantom: #core::marker::PhantomData,
+        });
+        quote! {
+            #builder_name {
+                #(#definitions)*
+                #phantom_definition
             }
         }
-        _ => unreachable!(),
     };
 
-    let builder_fields_definition = match builder_fields_definition_stream {
-        Ok(stream) => stream,
-        Err(e) => return syn::Error::into_compile_error(e).into(),
+    let from_value = builder_fields_definition(&|member| quote!(value.#member));
+    let from_impl = quote! {
+        impl #impl_generics #core::convert::From<#base_name #ty_generics> for #builder_type #where_clause {
+            fn from(value: #base_name #ty_generics) -> Self {
+                #from_value
+            }
+        }
+    };
+
+    // `to_builder` clones the fields one by one, so that it only needs the
+    // fields in the builder to be Clone. The bounds are higher-ranked to
+    // hold off checking them until it gets called, since a bound on a
+    // concrete type that doesn't hold is an error otherwise.
+    let clone_bounds = fields.iter().map(|f| {
+        let ty = f.ty;
+        quote!(for<'__a> #ty: #core::clone::Clone)
+    });
+    let from_self = builder_fields_definition(&|member| quote!(#core::clone::Clone::clone(&self.#member)));
+    let builder_vis = options.builder_vis(input);
+    let to_builder = quote! {
+        #builder_vis fn to_builder(&self) -> #builder_type
+        where
+            #(#clone_bounds,)*
+        {
+            #from_self
+        }
     };
 
+    (from_impl, to_builder)
+}
+
+// One builder to generate, for a struct or for a single variant of an enum.
+struct BuilderTarget<'a> {
+    builder_name: Ident,
+    // function on the input type returning an empty builder.
+    constructor: Ident,
+    // path of the struct expression in `build`, `Foo` or `Foo::Variant`.
+    path: proc_macro2::TokenStream,
+    fields: Vec<BuilderField<'a>>,
+    // `#[builder(skip)]` fields, absent from the builder and only filled in
+    // by `build`.
+    skipped: Vec<BuilderField<'a>>,
+}
+
+impl<'a> BuilderTarget<'a> {
+    fn new(builder_name: Ident, constructor: Ident, path: proc_macro2::TokenStream, fields: Vec<BuilderField<'a>>) -> Self {
+        let (skipped, fields) = fields.into_iter().partition(|f| f.skip);
+        BuilderTarget { builder_name, constructor, path, fields, skipped }
+    }
+}
+
+// builder struct along with its setters and build function. The builder
+// takes the same generic parameters and where clause as the input type.
+fn generate_builder(target: &BuilderTarget, input: &DeriveInput, options: &StructOptions) -> proc_macro2::TokenStream {
+    let roots = options.roots();
+    let Roots { core, .. } = &roots;
+    let BuilderTarget { builder_name, fields, .. } = target;
+
+    let base_name = &input.ident;
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let base_type = quote!(#base_name #ty_generics);
+    let builder_vis = options.builder_vis(input);
+
+    let builder_fields_declaration = fields.iter().map(|f| {
+        let name = &f.ident;
+        let ty = f.ty;
+        let attrs = &f.field_attrs;
+        quote! {
+            #(#[#attrs])*
+            #name : #core::option::Option<#ty>,
+        }
+    });
+
     // tokenstream of combined code for builder setter functions.
-    let builder_setter_functions_stream = match &input.data {
-        Struct(data_struct) => {
-            match &data_struct.fields {
-                Fields::Named(fields) => {
-                    fields.named.iter().map(|f| {
-                        // filter list attributes with path 'builder'
-                        let attrs = f.attrs.iter().filter(|attr| {
-                            if let syn::Meta::List(syn::MetaList {path, ..}) = &attr.meta {
-                                if path.is_ident("builder") {
-                                    true
-                                } else {
-                                    false
-                                }
-                            } else {
-                                false
-                            }
-                        }).collect::<Vec<_>>();
-
-                        let one_by_one_setter = one_by_one_setter(&attrs)?;
-                        let setter_name = &f.ident;
-                        let arg_ty = &f.ty;
-                        if let Some(one_by_one) = one_by_one_setter {
-                            let mut stream = proc_macro2::TokenStream::new();
-                            // the field is a vector of something. Get that something.
-
-                            let vec_inner_type = if let syn::Type::Path(TypePath {path: syn::Path {segments, ..}, ..}) = arg_ty {
-                                if let syn::PathSegment {arguments: syn::PathArguments::AngleBracketed(syn::AngleBracketedGenericArguments { args, ..}), ..} = &segments[0] {
-                                    match &args[0] {
-                                        syn::GenericArgument::Type(ty) => ty,
-                                        _ => unreachable!("wrong vec inner type"),
-                                    }
-                                } else {
-                                    unreachable!("foo");
-                                }
-                            } else {
-                                unreachable!("bar");
-                            };
-
-                            stream.extend(vec![quote! { 
-                                fn #one_by_one(&mut self, arg: #vec_inner_type) -> &mut Self {
-                                    self.#setter_name.get_or_insert_with(std::vec::Vec::new).push(arg);
-                                    self
-                                }
-                            }]);
-
-                            // conditionally generate all-at-once-builder
-                            if setter_name.as_ref().unwrap() != &one_by_one {
-                                stream.extend(vec![quote! { 
-                                    fn #setter_name(&mut self, arg: #arg_ty) -> &mut Self {
-                                        self.#setter_name = std::option::Option::Some(arg);
-                                        self
-                                    }
-                                }]);
-
-                            }
-
-                            Ok(stream)
-                        } else {
-                            match is_type_option_of_something(arg_ty) {
-                                Some(ty) => Ok(quote! {
-                                    fn #setter_name(&mut self, arg: #ty) -> &mut Self {
-                                        self.#setter_name = std::option::Option::Some(std::option::Option::Some(arg));
-                                        self
-                                    }
-                                }),
-                                None => Ok(quote! {
-                                    fn #setter_name(&mut self, arg: #arg_ty) -> &mut Self {
-                                        self.#setter_name = std::option::Option::Some(arg);
-                                        self
-                                    }
-                                }),
-                            }
-                        }
-                    }).collect::<syn::Result<proc_macro2::TokenStream>>()
+    let builder_setter_functions = fields.iter().map(|f| generate_setters(f, options.pattern, &roots));
+
+    let phantom_declaration = phantom_field(input, &roots).map(|ty| quote! {
+        __phantom: #ty,
+    });
+
+    let error_name = format_ident!("{}Error", builder_name);
+    let error = generate_error(&error_name, &builder_vis, &roots);
+
+    let reverse_builder = generate_reverse_builder(target, input, &base_type, &error_name, options);
+    let getters = generate_getters(fields, &builder_vis, &roots);
+    let merge = generate_merge(fields, &builder_vis, &roots);
+
+    let struct_attrs = options.struct_attrs();
+
+    quote! {
+        #struct_attrs
+        #builder_vis struct #builder_name #generics #where_clause {
+            #(#builder_fields_declaration)*
+            #phantom_declaration
+        }
+
+        #error
+
+        impl #impl_generics #builder_name #ty_generics #where_clause {
+            #(#builder_setter_functions)*
+
+            #getters
+
+            #merge
+
+            #reverse_builder
+        }
+    }
+}
+
+// read access to a builder that is being filled in, `get_x` and `is_x_set` for
+// every field and `missing_fields` listing the required ones that aren't set
+// yet. A typestate builder has the fields that are set in its type instead.
+// Like `merge` and `or`, a method is left out when a setter has its name.
+fn generate_getters(fields: &[BuilderField], vis: &syn::Visibility, roots: &Roots) -> proc_macro2::TokenStream {
+    let Roots { core, alloc } = roots;
+    let taken = setter_names(fields);
+    let free = |method: &Ident| !taken.contains(method);
+
+    let getters = fields.iter().map(|f| {
+        let name = &f.ident;
+        // `get_0` rather than `get__0` for the builder field `_0` that stands
+        // for the first field of a tuple struct.
+        let (get, is_set) = match &f.member {
+            Member::Unnamed(index) if *name == format!("_{}", index.index) => (
+                format_ident!("get_{}", index, span = name.span()),
+                format_ident!("is_{}_set", index, span = name.span()),
+            ),
+            _ => (format_ident!("get_{}", name), format_ident!("is_{}_set", name)),
+        };
+        let field_vis = &f.vis;
+        // an Option field holds the Option given by its setter.
+        let (ty, value) = match &f.option {
+            Some(inner) => (inner, quote! {
+                match &self.#name {
+                    #core::option::Option::Some(#core::option::Option::Some(value)) => #core::option::Option::Some(value),
+                    _ => #core::option::Option::None,
                 }
-                Fields::Unnamed(_fields) => {
-                    unimplemented!();
+            }),
+            None => (f.ty, quote!(#core::option::Option::as_ref(&self.#name))),
+        };
+        // `get__a` for a field `_a` is as snake case as the field is.
+        let get = free(&get).then(|| quote! {
+            #[allow(non_snake_case)]
+            #field_vis fn #get(&self) -> #core::option::Option<&#ty> {
+                #value
+            }
+        });
+        let is_set = free(&is_set).then(|| quote! {
+            #[allow(non_snake_case)]
+            #field_vis fn #is_set(&self) -> bool {
+                #core::option::Option::is_some(&self.#name)
+            }
+        });
+        quote! {
+            #get
+            #is_set
+        }
+    });
+
+    let missing = missing_fields(fields, roots);
+    let missing_fields = free(&format_ident!("missing_fields")).then(|| quote! {
+        #vis fn missing_fields(&self) -> #alloc::vec::Vec<&'static str> {
+            #missing
+        }
+    });
+
+    quote! {
+        #(#getters)*
+        #missing_fields
+    }
+}
+
+// expression listing the required fields of the builder that aren't set, by
+// their builder field names: the `name = "..."` of a renamed field and `_N`
+// for a tuple field.
+fn missing_fields(fields: &[BuilderField], roots: &Roots) -> proc_macro2::TokenStream {
+    let Roots { core, alloc } = roots;
+    let check_missing = fields.iter().filter(|f| f.is_required()).map(|f| {
+        let name = &f.ident;
+        let name_str = name.unraw().to_string();
+        quote! {
+            if #core::option::Option::is_none(&self.#name) {
+                missing.push(#name_str);
+            }
+        }
+    }).collect::<Vec<_>>();
+
+    if check_missing.is_empty() {
+        quote!(#alloc::vec::Vec::new())
+    } else {
+        quote! {{
+            let mut missing = #alloc::vec::Vec::new();
+            #(#check_missing)*
+            missing
+        }}
+    }
+}
+
+// `merge` and `or`, combining two builders of the same type field by field,
+// for example to layer configurations. Either is left out when a field has a
+// setter by its name. A typestate builder has none, since the result of
+// merging would need a type depending on both of them.
+fn generate_merge(fields: &[BuilderField], vis: &syn::Visibility, roots: &Roots) -> proc_macro2::TokenStream {
+    let Roots { core, .. } = roots;
+    let merge_fields = fields.iter().map(|f| {
+        let name = &f.ident;
+        if f.merge_append {
+            quote! {
+                match (&mut self.#name, other.#name) {
+                    (#core::option::Option::Some(items), #core::option::Option::Some(other)) => #core::iter::Extend::extend(items, other),
+                    (items, other @ #core::option::Option::Some(_)) => *items = other,
+                    _ => {}
                 }
-                Fields::Unit => {
-                    unimplemented!();
+            }
+        } else {
+            quote! {
+                if #core::option::Option::is_some(&other.#name) {
+                    self.#name = other.#name;
                 }
             }
         }
-        _ => unreachable!(),
-    };
+    });
 
-    let builder_setter_functions = match builder_setter_functions_stream {
-        Ok(stream) => stream,
-        Err(e) => return syn::Error::into_compile_error(e).into(),
-    };
+    let or_fields = fields.iter().map(|f| {
+        let name = &f.ident;
+        quote! {
+            if #core::option::Option::is_none(&self.#name) {
+                self.#name = fallback.#name;
+            }
+        }
+    });
+
+    let taken = setter_names(fields);
+    let merge = (!taken.iter().any(|name| name == "merge")).then(|| quote! {
+        // fields set in `other` win over the ones set here.
+        #vis fn merge(&mut self, other: Self) -> &mut Self {
+            #(#merge_fields)*
+            self
+        }
+    });
+    let or = (!taken.iter().any(|name| name == "or")).then(|| quote! {
+        // fields that aren't set here are taken from `fallback`.
+        #vis fn or(&mut self, fallback: Self) -> &mut Self {
+            #(#or_fields)*
+            self
+        }
+    });
+
+    quote! {
+        #merge
+        #or
+    }
+}
+
+// names of the setters of the fields, which take precedence over the other
+// methods of the builder.
+fn setter_names(fields: &[BuilderField]) -> Vec<Ident> {
+    fields.iter().flat_map(|f| {
+        let each = f.each.as_ref().map(|each| each.name.clone());
+        let try_setter = f.try_setter.then(|| format_ident!("try_{}", f.ident));
+        std::iter::once(f.ident.clone()).chain(each).chain(try_setter)
+    }).collect()
+}
+
+// Error returned by `build` of a non-typestate builder, `XBuilderError` for
+// `XBuilder`. Being a concrete enum it can be matched on to find out which
+// fields were missing.
+fn generate_error(error_name: &Ident, vis: &syn::Visibility, roots: &Roots) -> proc_macro2::TokenStream {
+    let Roots { core, alloc } = roots;
+    quote! {
+        #[derive(Debug)]
+        #vis enum #error_name {
+            // required fields that were never set, in declaration order.
+            UninitializedFields(#alloc::vec::Vec<&'static str>),
+            // message returned by the `build_fn(validate = "...")` function.
+            ValidationError(#alloc::string::String),
+        }
+
+        impl #core::fmt::Display for #error_name {
+            fn fmt(&self, f: &mut #core::fmt::Formatter<'_>) -> #core::fmt::Result {
+                match self {
+                    #error_name::UninitializedFields(fields) => {
+                        let (last, rest) = match fields.split_last() {
+                            #core::option::Option::Some(split) => split,
+                            #core::option::Option::None => return f.write_str("no fields are missing"),
+                        };
+                        if rest.is_empty() {
+                            return #core::write!(f, "field `{}` is not set", last);
+                        }
+                        f.write_str("fields ")?;
+                        for field in rest {
+                            #core::write!(f, "`{}`, ", field)?;
+                        }
+                        #core::write!(f, "`{}` are not set", last)
+                    }
+                    #error_name::ValidationError(message) => f.write_str(message),
+                }
+            }
+        }
+
+        impl #core::error::Error for #error_name {}
+    }
+}
+
+// Typestate builder for `#[builder(typestate)]`. Every required field is
+// stored in a type parameter of the builder, `()` until its setter has been
+// called and the field type afterwards, and `build` is only implemented once
+// all of them are set. Forgetting a required field is then a type error
+// instead of a runtime one, and since setters take the builder by value
+// nothing needs to be cloned.
+fn generate_typestate_builder(target: &BuilderTarget, input: &DeriveInput, options: &StructOptions) -> proc_macro2::TokenStream {
+    let roots = options.roots();
+    let Roots { core, .. } = &roots;
+    let BuilderTarget { builder_name, path, fields, .. } = target;
+
+    let base_name = &input.ident;
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    let builder_vis = options.builder_vis(input);
+    let states = typestate_params(fields);
+    let args = generic_arguments(&input.generics);
 
-    let reverse_builder = generate_reverse_builder(&input.data, &base_name);
+    // user generics followed by one parameter per required field.
+    let mut declaration_generics = input.generics.clone();
+    let mut setter_generics = input.generics.clone();
+    for state in states.iter().flatten() {
+        declaration_generics.params.push(syn::parse_quote!(#state = ()));
+        setter_generics.params.push(syn::parse_quote!(#state));
+    }
+    let (setter_impl_generics, _, _) = setter_generics.split_for_impl();
 
-    TokenStream::from(quote!{
-        pub struct #builder_name {
-            #builder_fields_declaration
+    let builder_fields_declaration = fields.iter().zip(&states).map(|(f, state)| {
+        let name = &f.ident;
+        let ty = f.ty;
+        let attrs = &f.field_attrs;
+        match state {
+            Some(state) => quote!(#(#[#attrs])* #name: #state,),
+            None => quote!(#(#[#attrs])* #name: #core::option::Option<#ty>,),
         }
+    });
+
+    let phantom_declaration = phantom_field(input, &roots).map(|ty| quote! {
+        __phantom: #ty,
+    });
+    let phantom_definition = phantom_field(input, &roots).map(|_| quote! {
+        __phantom: #core::marker::PhantomData,
+    });
+
+    // setting a required field moves every other field into a builder whose
+    // state parameter for it is the field type.
+    let builder_setter_functions = fields.iter().zip(&states).map(|(f, state)| {
+        if state.is_none() {
+            return generate_setters(f, Pattern::Owned, &roots);
+        }
+
+        let setter_name = &f.ident;
+        let (arg, value) = setter_argument(&roots, "arg", f.ty, f.into);
+        let next_states = fields.iter().zip(&states).filter_map(|(other, other_state)| {
+            let other_state = other_state.as_ref()?;
+            if other.ident == f.ident {
+                let ty = other.ty;
+                Some(quote!(#ty))
+            } else {
+                Some(quote!(#other_state))
+            }
+        });
+        let next_builder = quote!(#builder_name<#(#args,)* #(#next_states),*>);
+        let moved_fields = |value: proc_macro2::TokenStream| fields.iter().map(move |other| {
+            let name = &other.ident;
+            if other.ident == f.ident {
+                quote!(#name: #value,)
+            } else {
+                quote!(#name: self.#name,)
+            }
+        }).collect::<proc_macro2::TokenStream>();
 
-        impl #base_name {
-            pub fn builder() -> #builder_name {
+        let BuilderField { vis, setter_attrs, .. } = f;
+        let set_fields = moved_fields(value);
+        let mut stream = quote! {
+            #(#[#setter_attrs])*
+            #vis fn #setter_name(self, #arg) -> #next_builder {
                 #builder_name {
-                    #builder_fields_definition
+                    #set_fields
+                    #phantom_definition
                 }
             }
+        };
+
+        if f.try_setter {
+            let try_setter_name = format_ident!("try_{}", setter_name);
+            let ty = f.ty;
+            let set_fields = moved_fields(quote!(#core::convert::TryInto::try_into(arg)?));
+            stream.extend(quote! {
+                #(#[#setter_attrs])*
+                #vis fn #try_setter_name<__V: #core::convert::TryInto<#ty>>(self, arg: __V) -> #core::result::Result<#next_builder, __V::Error> {
+                    #core::result::Result::Ok(#builder_name {
+                        #set_fields
+                        #phantom_definition
+                    })
+                }
+            });
         }
 
-        impl #builder_name {
-            #builder_setter_functions
+        stream
+    });
 
-            #reverse_builder
+    let current_states = states.iter().flatten();
+    let set_states = fields.iter().zip(&states).filter(|(_, state)| state.is_some()).map(|(f, _)| f.ty);
+    let recurse = fields.iter().map(|f| {
+        let member = &f.member;
+        let name = &f.ident;
+        if let Some(default) = &f.default {
+            quote!(#member: match self.#name {
+                #core::option::Option::Some(val) => val,
+                #core::option::Option::None => #default,
+            },)
+        } else if f.is_required() {
+            quote!(#member: self.#name,)
+        } else if f.option.is_some() {
+            quote!(#member: #core::option::Option::flatten(self.#name),)
+        } else {
+            quote!(#member: #core::option::Option::unwrap_or_default(self.#name),)
+        }
+    });
+    let skipped = skipped_fields(&target.skipped, &roots);
+    let (impl_generics, _, _) = input.generics.split_for_impl();
+
+    let (build_name, build_vis) = options.build_fn(input);
+
+    // only a validation can still make a complete typestate builder fail, so
+    // `build` returns a Result and the builder gets an error type just then.
+    let (build, error) = match &options.validate {
+        Some(validate) => {
+            let error_name = format_ident!("{}Error", builder_name);
+            let build = quote! {
+                #build_vis fn #build_name(self) -> #core::result::Result<#base_name #ty_generics, #error_name> {
+                    if let #core::result::Result::Err(message) = #validate(&self) {
+                        return #core::result::Result::Err(#error_name::ValidationError(message));
+                    }
+
+                    #core::result::Result::Ok(#path {
+                        #(#recurse)*
+                        #skipped
+                    })
+                }
+            };
+            (build, generate_error(&error_name, &builder_vis, &roots))
         }
+        None => {
+            let build = quote! {
+                #build_vis fn #build_name(self) -> #base_name #ty_generics {
+                    #path {
+                        #(#recurse)*
+                        #skipped
+                    }
+                }
+            };
+            (build, quote!())
+        }
+    };
+
+    let struct_attrs = options.struct_attrs();
+
+    quote! {
+        #struct_attrs
+        #builder_vis struct #builder_name #declaration_generics #where_clause {
+            #(#builder_fields_declaration)*
+            #phantom_declaration
+        }
+
+        #error
+
+        impl #setter_impl_generics #builder_name<#(#args,)* #(#current_states),*> #where_clause {
+            #(#builder_setter_functions)*
+        }
+
+        impl #impl_generics #builder_name<#(#args,)* #(#set_states),*> #where_clause {
+            #build
+        }
+    }
+}
+
+// How `build` gets the values out of the builder fields.
+enum BuildValues {
+    // out of `self`, consuming the builder.
+    Move,
+    // with `Option::take` out of `&mut self`, leaving the builder empty.
+    Take,
+    // cloned out of `&self`, keeping the builder as it was.
+    Clone,
+}
+
+// Values of the skipped fields in the struct expression of `build`, their
+// `default` expression or `Default::default()`.
+fn skipped_fields(skipped: &[BuilderField], roots: &Roots) -> proc_macro2::TokenStream {
+    let Roots { core, .. } = roots;
+    skipped.iter().map(|f| {
+        let member = &f.member;
+        match &f.default {
+            Some(default) => quote!(#member: #default,),
+            None => quote!(#member: #core::default::Default::default(),),
+        }
+    }).collect()
+}
 
+// Names of the typestate parameters, `__S0`, `__S1`, ..., one for every
+// required field and none for the others.
+fn typestate_params(fields: &[BuilderField]) -> Vec<Option<Ident>> {
+    let mut count = 0usize;
+    fields.iter().map(|f| {
+        if f.is_required() {
+            count += 1;
+            Some(format_ident!("__S{}", count - 1))
+        } else {
+            None
+        }
+    }).collect()
+}
+
+// Generic arguments matching the parameters of the input, `'a, T, N` for
+// `<'a, T: Trait, const N: usize>`, to be followed by more arguments.
+fn generic_arguments(generics: &syn::Generics) -> Vec<proc_macro2::TokenStream> {
+    generics.params.iter().map(|param| match param {
+        syn::GenericParam::Lifetime(param) => {
+            let lifetime = &param.lifetime;
+            quote!(#lifetime)
+        }
+        syn::GenericParam::Type(param) => {
+            let ident = &param.ident;
+            quote!(#ident)
+        }
+        syn::GenericParam::Const(param) => {
+            let ident = &param.ident;
+            quote!(#ident)
+        }
+    }).collect()
+}
+
+// Type of the hidden `__phantom` field of a generic builder. Fields that don't
+// mention a parameter (like the other variants of an enum) would otherwise
+// leave it unused, so the builder marks all of them through the built type.
+fn phantom_field(input: &DeriveInput, roots: &Roots) -> Option<proc_macro2::TokenStream> {
+    let Roots { core, .. } = roots;
+    if input.generics.params.is_empty() {
+        return None;
+    }
+
+    let base_name = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    Some(quote! {
+        #core::marker::PhantomData<fn() -> #base_name #ty_generics>
     })
 }
 
-// not usable when more than one atributes are given
-fn one_by_one_setter(attrs: &[&syn::Attribute]) -> syn::Result<Option<Ident>> {
-    match attrs.len() {
-        0 => Ok(None),
-        1 => {
-            let expr_assign: syn::ExprAssign = match &attrs[0].parse_args()? {
-                syn::Expr::Assign(expr_assign) => expr_assign.clone(),
-                _ => todo!(),
-            };
+// `PingPong` -> `ping_pong`, for naming per-variant builder functions.
+fn to_snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut snake = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i != 0 {
+            // words start after a lowercase letter or digit, and an acronym
+            // ends before its last capital when a lowercase letter follows,
+            // `HTTPRequest` being `http_request`.
+            let previous = chars[i - 1];
+            let next_is_lowercase = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if previous.is_lowercase() || previous.is_ascii_digit() || (previous.is_uppercase() && next_is_lowercase) {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+// A field of the input struct as seen by the builder. Named fields keep their
+// name on the builder, positional fields of tuple structs become `_0`, `_1`,
+// ... unless renamed with `#[builder(name = "...")]`.
+struct BuilderField<'a> {
+    // how the field is accessed on the built struct, `name` or `0`.
+    member: Member,
+    // name of the builder field and of its setter.
+    ident: Ident,
+    // visibility of the setters.
+    vis: syn::Visibility,
+    ty: &'a Type,
+    // `T` of an `Option<T>` field, which its setter takes. None for a
+    // `required` one, whose setter takes the whole Option.
+    option: Option<Type>,
+    // unset Option fields and `optional` fields of other types are left to
+    // `None` and `Default::default()` by `build`.
+    optional: bool,
+    each: Option<EachSetter>,
+    default: Option<syn::Expr>,
+    // setters take `impl Into<T>`, for the field or for the whole struct.
+    into: bool,
+    try_setter: bool,
+    skip: bool,
+    // attributes of the setters, for the whole struct and for the field.
+    setter_attrs: Vec<syn::Meta>,
+    // attributes of the builder field.
+    field_attrs: Vec<syn::Meta>,
+    // `merge` extends the collection of an `each` field instead of replacing
+    // it.
+    merge_append: bool,
+}
+
+impl BuilderField<'_> {
+    // required fields must be set before `build`, the rest have a fallback.
+    fn is_required(&self) -> bool {
+        self.each.is_none() && self.default.is_none() && !self.optional
+    }
+}
+
+// `variant_vis` is the visibility of the enum for the fields of its variants,
+// which can't have one of their own.
+fn builder_fields<'a>(fields: &'a Fields, struct_options: &StructOptions, variant_vis: Option<&syn::Visibility>) -> syn::Result<Vec<BuilderField<'a>>> {
+    fields.iter().enumerate().map(|(index, f)| {
+        // filter list attributes with path 'builder'
+        let attrs = f.attrs.iter().filter(|attr| {
+            matches!(&attr.meta, syn::Meta::List(syn::MetaList {path, ..}) if path.is_ident("builder"))
+        }).collect::<Vec<_>>();
+
+        let FieldOptions {
+            each, name: rename, default, into, try_setter, skip, vis, optional, required, setter_attrs, field_attrs,
+            merge_append,
+        } = field_options(&attrs, &struct_options.roots())?;
+
+        let member = match &f.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(syn::Index::from(index)),
+        };
+
+        let ident = match (rename, &f.ident) {
+            (Some(rename), _) => rename,
+            (None, Some(ident)) => ident.clone(),
+            (None, None) => format_ident!("_{}", index, span = f.ty.span()),
+        };
+
+        // the item type of an `each` collection has to be known to declare
+        // the one-at-a-time setter.
+        let each = match each {
+            Some(Each { name, item: Some(item) }) => Some(EachSetter { name, item: CollectionItem::Single(item) }),
+            Some(Each { name, item: None }) => match collection_item(&f.ty) {
+                Some(item) => Some(EachSetter { name, item }),
+                None => return Err(syn::Error::new(f.ty.span(), "can't find the item type that `each` adds to this field, give it with `each(name = \"...\", item = \"...\")`")),
+            },
+            None => None,
+        };
+
+        // `optional` and `required` override what the type looks like, for
+        // example for an alias of an Option.
+        let option = match required {
+            true => None,
+            false => is_type_option_of_something(&f.ty),
+        };
+        let optional = optional || option.is_some();
+
+        // setters are as visible as the field they set.
+        let vis = vis.or_else(|| variant_vis.cloned()).unwrap_or_else(|| f.vis.clone());
+
+        Ok(BuilderField {
+            member,
+            ident,
+            vis,
+            ty: &f.ty,
+            option,
+            optional,
+            each,
+            default,
+            into: into || struct_options.setter_into,
+            try_setter,
+            skip,
+            setter_attrs: struct_options.setter_attrs.iter().cloned().chain(setter_attrs).collect(),
+            field_attrs,
+            merge_append,
+        })
+    }).collect()
+}
+
+// setter(s) of a single builder field, in the shape of the builder pattern.
+fn generate_setters(f: &BuilderField, pattern: Pattern, roots: &Roots) -> proc_macro2::TokenStream {
+    let Roots { core, .. } = roots;
+    let setter_name = &f.ident;
+    let arg_ty = f.ty;
+    let mut stream = proc_macro2::TokenStream::new();
+    if let Some(each) = &f.each {
+        // the one-at-a-time setter extends the collection with a single
+        // item, so anything that is `Default + Extend<item>` works.
+        let (args, item) = match &each.item {
+            CollectionItem::Single(ty) => setter_argument(roots, "arg", ty, f.into),
+            CollectionItem::Pair(key_ty, value_ty) => {
+                let (key_arg, key) = setter_argument(roots, "key", key_ty, f.into);
+                let (value_arg, value) = setter_argument(roots, "value", value_ty, f.into);
+                (quote!(#key_arg, #value_arg), quote!((#key, #value)))
+            }
+        };
+        let one_by_one = &each.name;
+        stream.extend(setter_method(pattern, roots, f, quote!(#one_by_one), args, None, |builder| quote! {
+            #core::iter::Extend::extend(
+                #builder.#setter_name.get_or_insert_with(#core::default::Default::default),
+                #core::iter::once(#item),
+            )
+        }));
+
+        // conditionally generate all-at-once-builder
+        if setter_name != one_by_one {
+            let (arg, value) = setter_argument(roots, "arg", arg_ty, f.into);
+            stream.extend(setter_method(pattern, roots, f, quote!(#setter_name), arg, None, |builder| quote! {
+                #builder.#setter_name = #core::option::Option::Some(#value)
+            }));
+        }
+    } else {
+        // setters of Option fields take the inner type.
+        let inner_ty = f.option.as_ref();
+        let value_ty = inner_ty.unwrap_or(arg_ty);
+        let wrap = |value: proc_macro2::TokenStream| match inner_ty {
+            Some(_) => quote!(#core::option::Option::Some(#value)),
+            None => value,
+        };
+
+        let (arg, value) = setter_argument(roots, "arg", value_ty, f.into);
+        let value = wrap(value);
+        stream.extend(setter_method(pattern, roots, f, quote!(#setter_name), arg, None, |builder| quote! {
+            #builder.#setter_name = #core::option::Option::Some(#value)
+        }));
+
+        // `try_` setter next to the regular one, failing with the error of
+        // the conversion.
+        if f.try_setter {
+            let try_setter_name = format_ident!("try_{}", setter_name);
+            let value = wrap(quote!(#core::convert::TryInto::try_into(arg)?));
+            stream.extend(setter_method(
+                pattern,
+                roots,
+                f,
+                quote!(#try_setter_name<__V: #core::convert::TryInto<#value_ty>>),
+                quote!(arg: __V),
+                Some(quote!(__V::Error)),
+                |builder| quote! {
+                    #builder.#setter_name = #core::option::Option::Some(#value)
+                },
+            ));
+        }
+    }
+    stream
+}
+
+// Setter method around an assignment to the builder, which the assignment gets
+// as its argument. Mutable builders are changed in place through `&mut self`,
+// owned builders are taken and returned by value and immutable builders hand
+// out a changed clone of themselves. Fallible setters wrap the returned
+// builder in a `Result` with the given error type.
+fn setter_method(
+    pattern: Pattern,
+    roots: &Roots,
+    f: &BuilderField,
+    // name of the setter followed by its generic parameters, if any.
+    name: proc_macro2::TokenStream,
+    arg: proc_macro2::TokenStream,
+    error: Option<proc_macro2::TokenStream>,
+    assignment: impl Fn(proc_macro2::TokenStream) -> proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let Roots { core, .. } = roots;
+    let (receiver, return_ty, builder, setup) = match pattern {
+        Pattern::Mutable => (quote!(&mut self), quote!(&mut Self), quote!(self), quote!()),
+        Pattern::Owned => (quote!(mut self), quote!(Self), quote!(self), quote!()),
+        Pattern::Immutable => (
+            quote!(&self),
+            quote!(Self),
+            quote!(new),
+            quote!(let mut new = #core::clone::Clone::clone(self);),
+        ),
+    };
+    let assignment = assignment(builder.clone());
+    let BuilderField { vis, setter_attrs, .. } = f;
+
+    match error {
+        Some(error) => quote! {
+            #(#[#setter_attrs])*
+            #vis fn #name(#receiver, #arg) -> #core::result::Result<#return_ty, #error> {
+                #setup
+                #assignment;
+                #core::result::Result::Ok(#builder)
+            }
+        },
+        None => quote! {
+            #(#[#setter_attrs])*
+            #vis fn #name(#receiver, #arg) -> #return_ty {
+                #setup
+                #assignment;
+                #builder
+            }
+        },
+    }
+}
+
+// Parameter `arg: ...` of a setter taking a `ty` and the expression converting
+// it into one. With `setter(into)` it accepts anything that is `Into<ty>`.
+fn setter_argument(roots: &Roots, arg: &str, ty: &Type, into: bool) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let Roots { core, .. } = roots;
+    let arg = Ident::new(arg, proc_macro2::Span::call_site());
+    if into {
+        (quote!(#arg: impl #core::convert::Into<#ty>), quote!(#core::convert::Into::into(#arg)))
+    } else {
+        (quote!(#arg: #ty), quote!(#arg))
+    }
+}
+
+// options given on a field through `#[builder(...)]`, several of them can be
+// listed in one attribute separated by commas.
+#[derive(Default)]
+struct FieldOptions {
+    // `each = "..."` or `each(name = "...", item = "...")`, one-at-a-time
+    // setter of a collection.
+    each: Option<Each>,
+    // `name = "..."`, name of the builder field and its setter.
+    name: Option<Ident>,
+    // `default` or `default = "expr"`, value used by `build` when unset.
+    default: Option<syn::Expr>,
+    // `setter(into)`, setters accept `impl Into<T>`.
+    into: bool,
+    // `try_setter`, also generate a fallible `try_` setter.
+    try_setter: bool,
+    // `skip`, leave the field out of the builder.
+    skip: bool,
+    // `vis = "..."`, visibility of the setters instead of the field's.
+    vis: Option<syn::Visibility>,
+    // `optional`, the field can be left unset whatever its type.
+    optional: bool,
+    // `required`, the field must be set even when it is an Option.
+    required: bool,
+    // `setter_attr(...)`, attributes of the setters of the field.
+    setter_attrs: Vec<syn::Meta>,
+    // `field_attr(...)`, attributes of the field in the builder.
+    field_attrs: Vec<syn::Meta>,
+    // `merge = "append"`, `merge` adds the items of an `each` collection to
+    // the ones already set instead of replacing them.
+    merge_append: bool,
+}
 
-            if let syn::Expr::Path(syn::ExprPath { path, ..}) = *expr_assign.left {
-                if path.is_ident("each") {
-                    if let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(litstr), ..}) = *expr_assign.right {
-                        Ok(Some(syn::Ident::new(&litstr.value(), proc_macro2::Span::call_site())))
+// options of a field, merged from all of its `builder` attributes. Every key
+// can be given once.
+fn field_options(attrs: &[&syn::Attribute], roots: &Roots) -> syn::Result<FieldOptions> {
+    let Roots { core, .. } = roots;
+    let mut options = FieldOptions::default();
+    let mut keys: Vec<Ident> = Vec::new();
+    for attr in attrs {
+        attr.parse_nested_meta(|meta| {
+            if let Some(key) = meta.path.get_ident() {
+                if keys.contains(key) {
+                    return Err(meta.error(format!("duplicate `{}`", key)));
+                }
+                keys.push(key.clone());
+            }
+
+            if meta.path.is_ident("each") {
+                options.each = Some(each_option(&meta)?);
+            } else if meta.path.is_ident("name") {
+                let litstr: syn::LitStr = meta.value()?.parse()?;
+                options.name = Some(syn::Ident::new(&litstr.value(), litstr.span()));
+            } else if meta.path.is_ident("default") {
+                // the expression is only evaluated by `build` when the
+                // field was not set.
+                options.default = Some(if meta.input.peek(syn::Token![=]) {
+                    let litstr: syn::LitStr = meta.value()?.parse()?;
+                    litstr.parse()?
+                } else {
+                    syn::parse_quote!(#core::default::Default::default())
+                });
+            } else if meta.path.is_ident("try_setter") {
+                options.try_setter = true;
+            } else if meta.path.is_ident("skip") {
+                options.skip = true;
+            } else if meta.path.is_ident("vis") {
+                let litstr: syn::LitStr = meta.value()?.parse()?;
+                options.vis = Some(litstr.parse()?);
+            } else if meta.path.is_ident("optional") {
+                options.optional = true;
+            } else if meta.path.is_ident("required") {
+                options.required = true;
+            } else if meta.path.is_ident("setter_attr") {
+                options.setter_attrs = attribute_list(&meta)?;
+            } else if meta.path.is_ident("field_attr") {
+                options.field_attrs = attribute_list(&meta)?;
+            } else if meta.path.is_ident("merge") {
+                let litstr: syn::LitStr = meta.value()?.parse()?;
+                options.merge_append = match litstr.value().as_str() {
+                    "append" => true,
+                    "replace" => false,
+                    _ => return Err(syn::Error::new(litstr.span(), "expected `\"append\"` or `\"replace\"`")),
+                };
+            } else if meta.path.is_ident("setter") {
+                meta.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("into") {
+                        options.into = true;
+                        Ok(())
                     } else {
-                        unreachable!("expected string literal for name");
+                        Err(meta.error("expected `setter(into)`"))
                     }
-                } else {
-                    return Err(syn::Error::new(path.span(), "expected `builder(each = \"...\")`"))
-                    // unreachable!("path == name, expected");
-                }
+                })?;
             } else {
-                unreachable!("left expression must be a path");
+                return Err(syn::Error::new(meta.path.span(), "expected `builder(each = \"...\")`"));
             }
+            Ok(())
+        })?;
+    }
 
-            // panic!("expr_assign, right: {:?}, left: {:?}", expr_assign.right, expr_assign.left);
+    // the later of two keys that contradict each other is pointed out.
+    for (first, second) in CONFLICTING_FIELD_OPTIONS {
+        let position = |name: &str| keys.iter().position(|key| key == name);
+        if let (Some(a), Some(b)) = (position(first), position(second)) {
+            return Err(syn::Error::new(keys[a.max(b)].span(), format!("`{}` can't be combined with `{}`", keys[a.max(b)], keys[a.min(b)])));
         }
-        _ => {
-            panic!("multiple attributes given where only one was expected");
+    }
+
+    // `merge` only tells how the collections of `each` fields are merged.
+    if let Some(merge) = keys.iter().find(|key| *key == "merge") {
+        if options.each.is_none() {
+            return Err(syn::Error::new(merge.span(), "`merge = \"...\"` needs `each`"));
         }
     }
+    Ok(options)
 }
 
-fn is_type_option_of_something(ty: &Type) -> Option<Type> {
-    match ty {
-        Type::Path(
-            TypePath {
-                qself: None,
-                path: Path {
-                    segments, .. // leading_colon
-                },
-            },
-        ) => {
-            match segments.iter().next() {
-                Some(path_segment) => {
-                    match path_segment {
-                        PathSegment {
-                            ident,
-                            arguments: PathArguments::AngleBracketed(
-                                         AngleBracketedGenericArguments {
-                                             args,
-                                             ..
-                                         },
-                                     )
-                            } => {
-                                if ident.to_string() == "Option" {
-                                    match args.iter().next() {
-                                        Some(gen_arg) => {
-                                            match gen_arg {
-                                                GenericArgument::Type(ty) => {
-                                                    Some(ty.clone())
-                                                }
-                                                _ => {
-                                                    None
-                                                }
-                                            }
-                                        }
-                                        None => {
-                                            None
-                                        }
-                                    }
-                                } else {
-                                    None
-                                }
-                            }
-                        _ => {
-                            None
-                        }
+// pairs of field options that contradict each other. A skipped field has no
+// setter to configure.
+const CONFLICTING_FIELD_OPTIONS: &[(&str, &str)] = &[
+    ("optional", "required"),
+    ("required", "default"),
+    ("required", "each"),
+    ("each", "try_setter"),
+    ("skip", "each"),
+    ("skip", "name"),
+    ("skip", "setter"),
+    ("skip", "try_setter"),
+    ("skip", "vis"),
+    ("skip", "optional"),
+    ("skip", "required"),
+    ("skip", "setter_attr"),
+    ("skip", "field_attr"),
+    ("skip", "merge"),
+];
+
+// one-at-a-time setter of a field, named `name`. Its argument is the item type
+// of the collection unless `item` gives it.
+struct Each {
+    name: Ident,
+    item: Option<Type>,
+}
+
+// `Each` of a builder field, with the item type looked up in the collection
+// when `item` doesn't give it.
+struct EachSetter {
+    name: Ident,
+    item: CollectionItem,
+}
+
+fn each_option(meta: &syn::meta::ParseNestedMeta) -> syn::Result<Each> {
+    if meta.input.peek(syn::Token![=]) {
+        let litstr: syn::LitStr = meta.value()?.parse()?;
+        return Ok(Each { name: syn::Ident::new(&litstr.value(), litstr.span()), item: None });
+    }
+
+    let mut name = None;
+    let mut item = None;
+    meta.parse_nested_meta(|meta| {
+        if meta.path.is_ident("name") {
+            let litstr: syn::LitStr = meta.value()?.parse()?;
+            name = Some(syn::Ident::new(&litstr.value(), litstr.span()));
+            Ok(())
+        } else if meta.path.is_ident("item") {
+            let litstr: syn::LitStr = meta.value()?.parse()?;
+            item = Some(litstr.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("expected `each(name = \"...\")` or `each(item = \"...\")`"))
+        }
+    })?;
+    match name {
+        Some(name) => Ok(Each { name, item }),
+        None => Err(meta.error("expected `each(name = \"...\")`")),
+    }
+}
+
+// contents of a `key(...)` holding attributes, which are given without their
+// `#[...]`.
+fn attribute_list(meta: &syn::meta::ParseNestedMeta) -> syn::Result<Vec<syn::Meta>> {
+    let content;
+    syn::parenthesized!(content in meta.input);
+    let attrs = content.parse_terminated(<syn::Meta as syn::parse::Parse>::parse, syn::Token![,])?;
+    Ok(attrs.into_iter().collect())
+}
+
+// crates the paths of the generated code start from, `std` for both unless
+// `no_std` asks for `core` and, for the types that allocate, `alloc`. The
+// crate using the derive then has to link `alloc` with `extern crate alloc`.
+struct Roots {
+    core: Ident,
+    alloc: Ident,
+}
+
+// options given on the struct or enum itself through `#[builder(...)]`.
+#[derive(Default)]
+struct StructOptions {
+    // `typestate`, track required fields in the builder's type.
+    typestate: bool,
+    // `no_std`, generate code that only needs `core` and `alloc`.
+    no_std: bool,
+    // `setter(into)`, make every setter generic over `Into`.
+    setter_into: bool,
+    // `pattern = "..."`, how setters and `build` receive the builder.
+    pattern: Pattern,
+    // `build_fn(clone)`, have `build` of a mutable builder clone the values
+    // instead of taking them out.
+    build_fn_clone: bool,
+    // `build_fn(validate = "path")`, function checking the builder before
+    // `build` assembles the struct.
+    validate: Option<syn::Path>,
+    // `build_fn(name = "...")` and `build_fn(vis = "...")`, defaulting to
+    // `pub fn build`. An empty `vis` makes it private.
+    build_fn_name: Option<Ident>,
+    build_fn_vis: Option<syn::Visibility>,
+    // `name = "..."`, name of the builder instead of `XBuilder`.
+    builder_name: Option<Ident>,
+    // `vis = "..."`, visibility of the builder, its error type and the
+    // functions creating and building it instead of the input's.
+    vis: Option<syn::Visibility>,
+    // `derive(...)`, traits derived for the builder.
+    derives: Vec<syn::Path>,
+    // `struct_attr(...)`, attributes of the builder struct.
+    struct_attrs: Vec<syn::Meta>,
+    // `setter_attr(...)`, attributes of every setter.
+    setter_attrs: Vec<syn::Meta>,
+}
+
+impl StructOptions {
+    fn roots(&self) -> Roots {
+        let (core, alloc) = match self.no_std {
+            true => ("core", "alloc"),
+            false => ("std", "std"),
+        };
+        Roots {
+            core: Ident::new(core, proc_macro2::Span::call_site()),
+            alloc: Ident::new(alloc, proc_macro2::Span::call_site()),
+        }
+    }
+
+    // derives and attributes of the builder struct. Setters of an immutable
+    // builder start from a clone of it, so it derives Clone even when it's
+    // not asked for.
+    fn struct_attrs(&self) -> proc_macro2::TokenStream {
+        let mut derives = self.derives.clone();
+        let derives_clone = derives.iter().any(|path| path.segments.last().is_some_and(|segment| segment.ident == "Clone"));
+        if self.pattern == Pattern::Immutable && !derives_clone {
+            derives.push(syn::parse_quote!(Clone));
+        }
+        let derive = (!derives.is_empty()).then(|| quote!(#[derive(#(#derives),*)]));
+        let attrs = &self.struct_attrs;
+        quote! {
+            #derive
+            #(#[#attrs])*
+        }
+    }
+
+    fn builder_vis(&self, input: &DeriveInput) -> syn::Visibility {
+        self.vis.clone().unwrap_or_else(|| input.vis.clone())
+    }
+
+    // name and visibility of the build function.
+    fn build_fn(&self, input: &DeriveInput) -> (Ident, syn::Visibility) {
+        let name = self.build_fn_name.clone().unwrap_or_else(|| format_ident!("build"));
+        let vis = self.build_fn_vis.clone().unwrap_or_else(|| self.builder_vis(input));
+        (name, vis)
+    }
+}
+
+// Builder patterns selected with `#[builder(pattern = "...")]`.
+#[derive(Clone, Copy, Default, PartialEq)]
+enum Pattern {
+    // setters take and return `&mut self`.
+    #[default]
+    Mutable,
+    // setters and `build` take `self` by value, nothing is cloned.
+    Owned,
+    // setters take `&self` and return a modified clone of the builder.
+    Immutable,
+}
+
+fn struct_options(attrs: &[syn::Attribute]) -> syn::Result<StructOptions> {
+    let mut options = StructOptions::default();
+    let mut pattern_span = None;
+    let mut build_fn_clone_span = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("builder")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("typestate") {
+                options.typestate = true;
+            } else if meta.path.is_ident("no_std") {
+                options.no_std = true;
+            } else if meta.path.is_ident("name") {
+                let litstr: syn::LitStr = meta.value()?.parse()?;
+                options.builder_name = Some(litstr.parse()?);
+            } else if meta.path.is_ident("vis") {
+                let litstr: syn::LitStr = meta.value()?.parse()?;
+                options.vis = Some(litstr.parse()?);
+            } else if meta.path.is_ident("pattern") {
+                let litstr: syn::LitStr = meta.value()?.parse()?;
+                options.pattern = match litstr.value().as_str() {
+                    "mutable" => Pattern::Mutable,
+                    "owned" => Pattern::Owned,
+                    "immutable" => Pattern::Immutable,
+                    _ => return Err(syn::Error::new(litstr.span(), "expected `\"owned\"`, `\"mutable\"` or `\"immutable\"`")),
+                };
+                pattern_span = Some(litstr.span());
+            } else if meta.path.is_ident("setter") {
+                meta.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("into") {
+                        options.setter_into = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("expected `setter(into)`"))
                     }
-                }
-                None => {
-                    None
-                }
+                })?;
+            } else if meta.path.is_ident("build_fn") {
+                meta.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("clone") {
+                        build_fn_clone_span = Some(meta.path.span());
+                        options.build_fn_clone = true;
+                        Ok(())
+                    } else if meta.path.is_ident("validate") {
+                        let litstr: syn::LitStr = meta.value()?.parse()?;
+                        options.validate = Some(litstr.parse()?);
+                        Ok(())
+                    } else if meta.path.is_ident("name") {
+                        let litstr: syn::LitStr = meta.value()?.parse()?;
+                        options.build_fn_name = Some(litstr.parse()?);
+                        Ok(())
+                    } else if meta.path.is_ident("vis") {
+                        let litstr: syn::LitStr = meta.value()?.parse()?;
+                        options.build_fn_vis = Some(litstr.parse()?);
+                        Ok(())
+                    } else {
+                        Err(meta.error("expected one of `clone`, `validate`, `name` or `vis` in `build_fn(...)`"))
+                    }
+                })?;
+            } else if meta.path.is_ident("derive") {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                options.derives.extend(content.parse_terminated(syn::Path::parse_mod_style, syn::Token![,])?);
+            } else if meta.path.is_ident("struct_attr") {
+                options.struct_attrs.extend(attribute_list(&meta)?);
+            } else if meta.path.is_ident("setter_attr") {
+                options.setter_attrs.extend(attribute_list(&meta)?);
+            } else {
+                return Err(meta.error("expected `builder(typestate)`, `builder(no_std)`, `builder(name = \"...\")`, `builder(vis = \"...\")`, `builder(pattern = \"...\")`, `builder(setter(into))`, `builder(build_fn(...))`, `builder(derive(...))`, `builder(struct_attr(...))` or `builder(setter_attr(...))`"));
             }
+            Ok(())
+        })?;
+    }
+
+    // typestate setters change the type of the builder, so they can only
+    // ever take it by value.
+    if let Some(span) = pattern_span {
+        if options.typestate && options.pattern != Pattern::Owned {
+            return Err(syn::Error::new(span, "typestate builders always use the owned pattern"));
         }
-        _ => {
-            None
+    }
+    if let Some(span) = build_fn_clone_span {
+        if options.typestate || options.pattern == Pattern::Owned {
+            return Err(syn::Error::new(span, "`build_fn(clone)` needs a builder that is not consumed by `build`"));
         }
     }
+    Ok(options)
 }
 
-fn generate_reverse_builder(data: &Data, base_name: &Ident) -> proc_macro2::TokenStream {
-    match &data {
-        Data::Struct(ref data) => {
-            match &data.fields {
-                Fields::Named(fields) => {
-                    let recurse = fields.named.iter().map(|f| {
-                        let name = &f.ident;
-                        let field_ty = &f.ty;
-                        match is_type_option_of_something(field_ty) {
-                            Some(_) => quote! {
-                                #name: match &self.#name {
-                                    std::option::Option::Some(val) => val.clone(),
-                                    std::option::Option::None => std::option::Option::None,
-                                },
-                            },
-                            None =>  quote! {
-                                #name: match &self.#name {
-                                    std::option::Option::Some(val) => val.clone(),
-                                    std::option::Option::None => return std::result::Result::Err(String::from("test").into()),
-                                },
-                            },
-                        }
-                    });
+// `#[builder(name = "...")]` on an enum variant, naming its builder.
+fn variant_builder_name(attrs: &[syn::Attribute]) -> syn::Result<Option<Ident>> {
+    let mut builder_name = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("builder")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                let litstr: syn::LitStr = meta.value()?.parse()?;
+                builder_name = Some(litstr.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `builder(name = \"...\")`"))
+            }
+        })?;
+    }
+    Ok(builder_name)
+}
 
-                    quote! {
-                        pub fn build(&mut self) -> std::result::Result<#base_name, std::boxed::Box<dyn std::error::Error>> {
-                            Ok(#base_name {
-                                #(#recurse)*
-                            })
-                        }
+// what an `each` setter adds to a collection: maps are extended with key-value
+// pairs, everything else with its first type argument.
+enum CollectionItem {
+    Single(Type),
+    // the value is boxed to keep the enum small.
+    Pair(Type, Box<Type>),
+}
+
+fn collection_item(ty: &Type) -> Option<CollectionItem> {
+    let Type::Path(TypePath { qself: None, path }) = ty else {
+        return None;
+    };
+    let PathSegment { ident, arguments: PathArguments::AngleBracketed(arguments) } = path.segments.last()? else {
+        return None;
+    };
+    let mut types = arguments.args.iter().filter_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    });
+    let first = types.next()?;
+    match types.next() {
+        // `HashMap<K, V>`, `BTreeMap<K, V>` and the like.
+        Some(second) if ident.to_string().ends_with("Map") => Some(CollectionItem::Pair(first.clone(), Box::new(second.clone()))),
+        _ => Some(CollectionItem::Single(first.clone())),
+    }
+}
+
+// `T` of an `Option<T>`, also when it is spelled out as
+// `std::option::Option<T>`, `core::option::Option<T>` or with a leading `::`.
+fn is_type_option_of_something(ty: &Type) -> Option<Type> {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) if is_std_path(path, "option", "Option") => {
+            match &path.segments.last()?.arguments {
+                PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. }) => {
+                    match args.iter().next() {
+                        Some(GenericArgument::Type(ty)) => Some(ty.clone()),
+                        _ => None,
                     }
                 }
-                _ => unimplemented!(),
+                _ => None,
             }
-        },
-        _ => unimplemented!(),
+        }
+        _ => None,
+    }
+}
+
+// whether `path` names the std item `name` from `module`, either by its bare
+// name or through the `std`, `core` or `alloc` crate.
+fn is_std_path(path: &Path, module: &str, name: &str) -> bool {
+    let idents = path.segments.iter().map(|segment| segment.ident.to_string()).collect::<Vec<_>>();
+    match idents.as_slice() {
+        [ident] => path.leading_colon.is_none() && ident == name,
+        [krate, m, ident] => matches!(krate.as_str(), "std" | "core" | "alloc") && m == module && ident == name,
+        _ => false,
+    }
+}
+
+fn generate_reverse_builder(target: &BuilderTarget, input: &DeriveInput, base_type: &proc_macro2::TokenStream, error_name: &Ident, options: &StructOptions) -> proc_macro2::TokenStream {
+    let roots = options.roots();
+    let Roots { core, .. } = &roots;
+    let BuilderTarget { path, fields, skipped, .. } = target;
+
+    let build_values = match options.pattern {
+        Pattern::Owned => BuildValues::Move,
+        Pattern::Mutable if !options.build_fn_clone => BuildValues::Take,
+        Pattern::Mutable | Pattern::Immutable => BuildValues::Clone,
+    };
+
+    // every required field is checked before anything is built so that the
+    // error can name all of the missing ones at once.
+    let missing_fields = if fields.iter().any(|f| f.is_required()) {
+        let missing = missing_fields(fields, &roots);
+        quote! {
+            let missing = #missing;
+            if !missing.is_empty() {
+                return #core::result::Result::Err(#error_name::UninitializedFields(missing));
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    let recurse = fields.iter().map(|f| {
+        let member = &f.member;
+        let name = &f.ident;
+        let unset = if let Some(default) = &f.default {
+            quote!(#default)
+        } else if f.is_required() {
+            quote!(#core::unreachable!())
+        } else if f.option.is_some() {
+            quote!(#core::option::Option::None)
+        } else {
+            quote!(#core::default::Default::default())
+        };
+        // an owned builder is consumed by `build` and gives up its values, a
+        // mutable one has them taken out and is left empty. Only builders
+        // that are kept intact hand out clones, which is what requires the
+        // field types to be Clone.
+        match build_values {
+            BuildValues::Move => quote! {
+                #member: match self.#name {
+                    #core::option::Option::Some(val) => val,
+                    #core::option::Option::None => #unset,
+                },
+            },
+            BuildValues::Take => quote! {
+                #member: match #core::option::Option::take(&mut self.#name) {
+                    #core::option::Option::Some(val) => val,
+                    #core::option::Option::None => #unset,
+                },
+            },
+            BuildValues::Clone => quote! {
+                #member: match &self.#name {
+                    #core::option::Option::Some(val) => #core::clone::Clone::clone(val),
+                    #core::option::Option::None => #unset,
+                },
+            },
+        }
+    });
+
+    let receiver = match build_values {
+        BuildValues::Move => quote!(self),
+        BuildValues::Take => quote!(&mut self),
+        BuildValues::Clone => quote!(&self),
+    };
+
+    // the validation sees the builder as it was filled in, before any value
+    // is taken out of it.
+    let builder_ref = match build_values {
+        BuildValues::Move => quote!(&self),
+        BuildValues::Take | BuildValues::Clone => quote!(&*self),
+    };
+    let validate = options.validate.as_ref().map(|validate| quote! {
+        if let #core::result::Result::Err(message) = #validate(#builder_ref) {
+            return #core::result::Result::Err(#error_name::ValidationError(message));
+        }
+    });
+
+    let skipped = skipped_fields(skipped, &roots);
+    let (build_name, build_vis) = options.build_fn(input);
+
+    // struct expression with `member: value` pairs works for tuple structs
+    // and tuple variants too, `Port { 0: .., 1: .. }`.
+    quote! {
+        #build_vis fn #build_name(#receiver) -> #core::result::Result<#base_type, #error_name> {
+            #missing_fields
+            #validate
+
+            #core::result::Result::Ok(#path {
+                #(#recurse)*
+                #skipped
+            })
+        }
     }
 }
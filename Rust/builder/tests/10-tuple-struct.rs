@@ -0,0 +1,39 @@
+// Tuple structs get a builder too. Positional fields are set through setters
+// named after their index, `_0`, `_1`, ..., or through a name given with
+// #[builder(name = "...")]. Option and Vec fields behave the same way they do
+// on structs with named fields.
+
+use derive_builder::Builder;
+
+#[derive(Clone)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+#[derive(Builder)]
+pub struct Port(u16, Protocol);
+
+#[derive(Builder)]
+pub struct Listener(
+    #[builder(name = "address")] String,
+    Option<u16>,
+    #[builder(each = "alias")] Vec<String>,
+);
+
+fn main() {
+    let port = Port::builder()._0(8080)._1(Protocol::Udp).build().unwrap();
+    assert_eq!(port.0, 8080);
+    assert!(matches!(port.1, Protocol::Udp));
+
+    assert!(Port::builder()._1(Protocol::Tcp).build().is_err());
+
+    let listener = Listener::builder()
+        .address("localhost".to_owned())
+        .alias("lo".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(listener.0, "localhost");
+    assert_eq!(listener.1, None);
+    assert_eq!(listener.2, vec!["lo"]);
+}
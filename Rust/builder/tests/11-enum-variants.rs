@@ -0,0 +1,56 @@
+// Enums get one builder per variant. For a variant `Connect` of `Message` the
+// macro generates a `MessageConnectBuilder` along with a `build_connect`
+// function on `Message` that returns an empty builder, whose `build` produces
+// `Message::Connect { .. }`. Struct-like and tuple variants are supported.
+// Acronyms make a single word of the function name, `build_http_request`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub enum Message {
+    Connect {
+        host: String,
+        port: Option<u16>,
+    },
+    Publish {
+        topic: String,
+        #[builder(each = "payload_part")]
+        payload: Vec<u8>,
+    },
+    KeepAlive(u32),
+    HTTPRequest {
+        path: String,
+    },
+    URL(String),
+    Disconnect,
+}
+
+fn main() {
+    let connect = Message::build_connect()
+        .host("localhost".to_owned())
+        .build()
+        .unwrap();
+    assert!(matches!(connect, Message::Connect { ref host, port: None } if host == "localhost"));
+
+    let publish: Message = Message::build_publish()
+        .topic("news".to_owned())
+        .payload_part(1)
+        .payload_part(2)
+        .build()
+        .unwrap();
+    assert!(matches!(publish, Message::Publish { ref payload, .. } if *payload == [1, 2]));
+
+    let mut keep_alive: MessageKeepAliveBuilder = Message::build_keep_alive();
+    let keep_alive = keep_alive._0(30).build().unwrap();
+    assert!(matches!(keep_alive, Message::KeepAlive(30)));
+
+    let request = Message::build_http_request().path("/".to_owned()).build().unwrap();
+    assert!(matches!(request, Message::HTTPRequest { ref path } if path == "/"));
+    let mut url: MessageURLBuilder = Message::build_url();
+    assert!(matches!(url._0("a".to_owned()).build().unwrap(), Message::URL(_)));
+
+    let disconnect = Message::build_disconnect().build().unwrap();
+    assert!(matches!(disconnect, Message::Disconnect));
+
+    assert!(Message::build_connect().build().is_err());
+}
@@ -0,0 +1,59 @@
+// Generic structs and enums get generic builders. Type parameters, lifetimes,
+// const generics and where clauses of the input are carried over to the
+// builder struct, its impl blocks and the `builder` function, so that
+//
+//     impl<'a, T: Backend> Pool<'a, T> where T: Send {
+//         pub fn builder() -> PoolBuilder<'a, T> { ... }
+//     }
+//
+// produces a PoolBuilder<'a, T> whose build returns Pool<'a, T>.
+
+use derive_builder::Builder;
+
+pub trait Backend {
+    fn name(&self) -> &str;
+}
+
+pub struct Memory;
+
+impl Backend for Memory {
+    fn name(&self) -> &str {
+        "memory"
+    }
+}
+
+#[derive(Builder)]
+pub struct Pool<'a, T: Backend, const N: usize>
+where
+    T: Send,
+{
+    name: &'a str,
+    backend: T,
+    slots: [u8; N],
+    #[builder(each = "tag")]
+    tags: Vec<&'a str>,
+}
+
+#[derive(Builder)]
+pub enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+fn main() {
+    let name = String::from("primary");
+    let pool = Pool::builder()
+        .name(&name)
+        .backend(Memory)
+        .slots([0; 4])
+        .tag("fast")
+        .build()
+        .unwrap();
+    assert_eq!(pool.name, "primary");
+    assert_eq!(pool.backend.name(), "memory");
+    assert_eq!(pool.slots.len(), 4);
+    assert_eq!(pool.tags, ["fast"]);
+
+    let left: Either<u8, String> = Either::build_left()._0(1).build().unwrap();
+    assert!(matches!(left, Either::Left(1)));
+}
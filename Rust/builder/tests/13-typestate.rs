@@ -0,0 +1,49 @@
+// With #[builder(typestate)] the builder keeps track of which required fields
+// have been set in its type parameters. Setters take the builder by value and
+// return a builder of a different type, and `build` only exists once every
+// field that is neither an Option nor has an `each` setter has been set. As
+// nothing can be missing at that point, `build` returns the struct itself
+// instead of a Result.
+//
+// Since values are moved through the builder, fields don't need to be Clone.
+
+use derive_builder::Builder;
+
+pub struct Socket {
+    fd: i32,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    socket: Socket,
+    current_dir: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Pair<'a, T> {
+    left: &'a T,
+    right: &'a T,
+}
+
+fn main() {
+    let command = Command::builder()
+        .arg("build".to_owned())
+        .socket(Socket { fd: 3 })
+        .executable("cargo".to_owned())
+        .arg("--release".to_owned())
+        .build();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.socket.fd, 3);
+    assert!(command.current_dir.is_none());
+
+    let (one, two) = (1, 2);
+    let pair = Pair::builder().right(&two).left(&one).build();
+    assert_eq!((*pair.left, *pair.right), (1, 2));
+}
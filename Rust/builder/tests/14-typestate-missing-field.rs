@@ -0,0 +1,18 @@
+// Forgetting a required field of a typestate builder is caught by the type
+// checker: `build` is not implemented for a builder whose state for that field
+// is still `()`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command {
+    executable: String,
+    current_dir: String,
+}
+
+fn main() {
+    let _command = Command::builder()
+        .executable("cargo".to_owned())
+        .build();
+}
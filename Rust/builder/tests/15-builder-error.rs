@@ -0,0 +1,29 @@
+// A failing `build` returns a concrete error enum generated next to the
+// builder, `CommandBuilderError` for `CommandBuilder`, instead of a boxed
+// string. It implements Display and std::error::Error and names the fields
+// that were not set, so callers can match on it.
+//
+//     pub enum CommandBuilderError {
+//         UninitializedFields(Vec<&'static str>),
+//         ValidationError(String),
+//     }
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let err = Command::builder().build().err().unwrap();
+    assert!(matches!(err, CommandBuilderError::UninitializedFields(ref fields) if *fields == ["executable"]));
+    assert_eq!(err.to_string(), "field `executable` is not set");
+
+    let err: Box<dyn std::error::Error> = Box::new(err);
+    assert_eq!(err.to_string(), "field `executable` is not set");
+
+    let validation = CommandBuilderError::ValidationError("bad path".to_owned());
+    assert_eq!(validation.to_string(), "bad path");
+}
@@ -0,0 +1,41 @@
+// A single call to `build` reports every required field that is still unset,
+// in the order the fields are declared, rather than stopping at the first one.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Config {
+    host: String,
+    port: u16,
+    #[builder(each = "peer")]
+    peers: Vec<String>,
+    user: Option<String>,
+    database: String,
+}
+
+fn main() {
+    let err = Config::builder().port(5432).build().err().unwrap();
+    match &err {
+        ConfigBuilderError::UninitializedFields(fields) => {
+            assert_eq!(fields, &["host", "database"]);
+        }
+        ConfigBuilderError::ValidationError(_) => unreachable!(),
+    }
+    assert_eq!(err.to_string(), "fields `host`, `database` are not set");
+
+    let err = Config::builder().build().err().unwrap();
+    assert_eq!(err.to_string(), "fields `host`, `port`, `database` are not set");
+
+    // `build` never reports an empty list, but the error can be made by hand.
+    let err = ConfigBuilderError::UninitializedFields(Vec::new());
+    assert_eq!(err.to_string(), "no fields are missing");
+
+    let config = Config::builder()
+        .database("app".to_owned())
+        .port(5432)
+        .host("localhost".to_owned())
+        .build()
+        .unwrap();
+    assert!(config.peers.is_empty());
+    assert!(config.user.is_none());
+}
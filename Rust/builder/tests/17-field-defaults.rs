@@ -0,0 +1,69 @@
+// Fields marked #[builder(default)] don't need to be set; `build` falls back
+// to Default::default() for them. With #[builder(default = "...")] any
+// expression can be given instead. It is only evaluated by `build` when the
+// field was not set, and can refer to anything in scope where the struct is
+// defined.
+
+use derive_builder::Builder;
+use std::cell::Cell;
+
+thread_local! {
+    static EVALUATED: Cell<u32> = const { Cell::new(0) };
+}
+
+fn default_timeout() -> u64 {
+    EVALUATED.with(|evaluated| evaluated.set(evaluated.get() + 1));
+    30
+}
+
+#[derive(Builder)]
+pub struct Server {
+    host: String,
+    #[builder(default = "8080")]
+    port: u16,
+    #[builder(default)]
+    verbose: bool,
+    #[builder(default = "default_timeout()")]
+    timeout: u64,
+    #[builder(default = "Some(String::from(\"admin\"))")]
+    user: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Client {
+    #[builder(default = "\"localhost\".to_owned()")]
+    host: String,
+    #[builder(default)]
+    retries: u8,
+}
+
+fn main() {
+    let server = Server::builder()
+        .host("localhost".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(server.port, 8080);
+    assert!(!server.verbose);
+    assert_eq!(server.timeout, 30);
+    assert_eq!(server.user.as_deref(), Some("admin"));
+    assert_eq!(EVALUATED.with(Cell::get), 1);
+
+    let server = Server::builder()
+        .host("localhost".to_owned())
+        .port(80)
+        .verbose(true)
+        .timeout(5)
+        .user("root".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(server.port, 80);
+    assert!(server.verbose);
+    assert_eq!(server.timeout, 5);
+    assert_eq!(server.user.as_deref(), Some("root"));
+    assert_eq!(EVALUATED.with(Cell::get), 1);
+
+    let client = Client::builder().retries(3).build();
+    assert_eq!(client.host, "localhost");
+    assert_eq!(client.retries, 3);
+}
@@ -0,0 +1,62 @@
+// With #[builder(setter(into))] on a field its setter accepts anything that
+// converts into the field type, so callers can pass "cargo" rather than
+// "cargo".to_owned(). Given on the struct it applies to every setter. For
+// Option fields the conversion targets the inner type, and for fields with an
+// `each` setter the one-at-a-time setter converts into the element type.
+
+use derive_builder::Builder;
+use std::path::PathBuf;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(setter(into))]
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(setter(into))]
+    current_dir: Option<PathBuf>,
+    timeout: u64,
+}
+
+#[derive(Builder)]
+#[builder(setter(into))]
+pub struct Env {
+    #[builder(each = "var")]
+    vars: Vec<String>,
+    home: PathBuf,
+    shell: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(typestate, setter(into))]
+pub struct User {
+    name: String,
+    uid: u64,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo")
+        .arg("build".to_owned())
+        .current_dir("/tmp")
+        .timeout(5)
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.current_dir, Some(PathBuf::from("/tmp")));
+
+    let env = Env::builder()
+        .var("PATH=/bin")
+        .var(String::from("TERM=xterm"))
+        .home("/root")
+        .shell("sh")
+        .build()
+        .unwrap();
+    assert_eq!(env.vars, ["PATH=/bin", "TERM=xterm"]);
+    assert_eq!(env.home, PathBuf::from("/root"));
+    assert_eq!(env.shell.as_deref(), Some("sh"));
+
+    let user = User::builder().uid(1000u32).name("ferris").build();
+    assert_eq!(user.name, "ferris");
+    assert_eq!(user.uid, 1000);
+}
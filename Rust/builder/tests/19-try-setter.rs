@@ -0,0 +1,48 @@
+// Fields marked #[builder(try_setter)] get a fallible `try_<field>` setter in
+// addition to the regular one. It accepts anything that is TryInto the field
+// type (the inner type for Option fields) and returns the conversion error
+// untouched when it fails.
+//
+//     fn try_port<V: TryInto<u16>>(&mut self, arg: V) -> Result<&mut Self, V::Error>
+
+use derive_builder::Builder;
+use std::num::TryFromIntError;
+
+#[derive(Builder)]
+pub struct Listener {
+    #[builder(try_setter)]
+    port: u16,
+    #[builder(try_setter)]
+    backlog: Option<u8>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Timeout {
+    #[builder(try_setter)]
+    seconds: u32,
+}
+
+fn main() -> Result<(), TryFromIntError> {
+    let listener = Listener::builder()
+        .try_port(8080u64)?
+        .try_backlog(16i32)?
+        .build()
+        .unwrap();
+    assert_eq!(listener.port, 8080);
+    assert_eq!(listener.backlog, Some(16));
+
+    let mut builder = Listener::builder();
+    let err: TryFromIntError = builder.try_port(-1i32).err().unwrap();
+    assert_eq!(err.to_string(), "out of range integral type conversion attempted");
+    assert!(builder.build().is_err());
+
+    builder.port(80);
+    assert_eq!(builder.build().unwrap().port, 80);
+
+    let timeout = Timeout::builder().try_seconds(30u64)?.build();
+    assert_eq!(timeout.seconds, 30);
+    assert!(Timeout::builder().try_seconds(u64::MAX).is_err());
+
+    Ok(())
+}
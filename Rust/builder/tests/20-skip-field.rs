@@ -0,0 +1,40 @@
+// Fields marked #[builder(skip)] are left out of the builder entirely: there is
+// no builder field and no setter for them. `build` fills them in with
+// Default::default(), or with the expression given through `default`.
+
+use derive_builder::Builder;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+#[derive(Builder)]
+pub struct Resolver<T> {
+    host: String,
+    #[builder(skip)]
+    cache: HashMap<String, String>,
+    #[builder(skip, default = "3")]
+    retries: u32,
+    #[builder(skip)]
+    marker: PhantomData<T>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Counter {
+    name: String,
+    #[builder(skip, default = "1")]
+    count: u64,
+}
+
+fn main() {
+    let resolver: Resolver<()> = Resolver::builder()
+        .host("localhost".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(resolver.host, "localhost");
+    assert!(resolver.cache.is_empty());
+    assert_eq!(resolver.retries, 3);
+    let _: PhantomData<()> = resolver.marker;
+
+    let counter = Counter::builder().name("hits".to_owned()).build();
+    assert_eq!(counter.count, 1);
+}
@@ -0,0 +1,14 @@
+// Skipped fields have no setter on the builder.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Resolver {
+    host: String,
+    #[builder(skip)]
+    retries: u32,
+}
+
+fn main() {
+    let _ = Resolver::builder().host("localhost".to_owned()).retries(3);
+}
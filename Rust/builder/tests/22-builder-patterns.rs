@@ -0,0 +1,61 @@
+// #[builder(pattern = "...")] selects how setters and `build` receive the
+// builder.
+//
+//   - "mutable", the default: setters take and return `&mut Self`.
+//   - "owned": setters take `self` by value and return it, and `build(self)`
+//     moves the values out of the builder, so field types need not be Clone.
+//   - "immutable": setters take `&self` and return a modified clone of the
+//     builder, so a partially filled builder can serve as a template.
+
+use derive_builder::Builder;
+
+pub struct Connection {
+    fd: i32,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Session {
+    connection: Connection,
+    #[builder(each = "header")]
+    headers: Vec<String>,
+    user: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "immutable")]
+pub struct Request {
+    method: String,
+    path: String,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "mutable")]
+pub struct Response {
+    status: u16,
+}
+
+fn main() {
+    let session = Session::builder()
+        .connection(Connection { fd: 7 })
+        .header("Accept: */*".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(session.connection.fd, 7);
+    assert_eq!(session.headers, ["Accept: */*"]);
+    assert!(session.user.is_none());
+
+    let err = Session::builder().user("me".to_owned()).build().err().unwrap();
+    assert_eq!(err.to_string(), "field `connection` is not set");
+
+    let get = Request::builder().method("GET".to_owned());
+    let index = get.path("/".to_owned()).build().unwrap();
+    let about = get.path("/about".to_owned()).build().unwrap();
+    assert_eq!((index.method.as_str(), index.path.as_str()), ("GET", "/"));
+    assert_eq!((about.method.as_str(), about.path.as_str()), ("GET", "/about"));
+    assert!(get.build().is_err());
+
+    let mut response = Response::builder();
+    response.status(404);
+    assert_eq!(response.build().unwrap().status, 404);
+}
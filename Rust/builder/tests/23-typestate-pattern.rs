@@ -0,0 +1,12 @@
+// Typestate builders change their type with every required field that is set,
+// so they can only use the owned pattern.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate, pattern = "mutable")]
+pub struct Command {
+    executable: String,
+}
+
+fn main() {}
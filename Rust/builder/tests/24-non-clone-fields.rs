@@ -0,0 +1,46 @@
+// Building doesn't require the field types to be Clone. `build(&mut self)` of
+// a mutable builder takes the values out of the builder with Option::take,
+// after checking that every required field is set, so the builder is empty
+// again afterwards. An owned builder moves them out instead.
+//
+// The clone-based `build(&self)`, which leaves the builder untouched, is only
+// used by immutable builders and by mutable ones that ask for it with
+// #[builder(build_fn(clone))].
+
+use derive_builder::Builder;
+use std::fs::File;
+
+#[derive(Builder)]
+pub struct Logger {
+    sink: Box<dyn Fn(&str) -> String>,
+    file: Option<File>,
+    level: u8,
+}
+
+#[derive(Builder)]
+#[builder(build_fn(clone))]
+pub struct Template {
+    name: String,
+}
+
+fn main() {
+    let mut builder = Logger::builder();
+    builder.sink(Box::new(|line| format!("> {}", line)));
+
+    // nothing is taken out when a required field is missing.
+    let err = builder.build().err().unwrap();
+    assert_eq!(err.to_string(), "field `level` is not set");
+
+    let logger = builder.level(2).build().unwrap();
+    assert_eq!((logger.sink)("ready"), "> ready");
+    assert!(logger.file.is_none());
+    assert_eq!(logger.level, 2);
+
+    let err = builder.build().err().unwrap();
+    assert_eq!(err.to_string(), "fields `sink`, `level` are not set");
+
+    let mut template = Template::builder();
+    template.name("base".to_owned());
+    assert_eq!(template.build().unwrap().name, "base");
+    assert_eq!(template.build().unwrap().name, "base");
+}
@@ -0,0 +1,11 @@
+// An owned builder is consumed by `build`, so there is nothing to clone from.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(pattern = "owned", build_fn(clone))]
+pub struct Template {
+    name: String,
+}
+
+fn main() {}
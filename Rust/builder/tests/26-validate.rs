@@ -0,0 +1,67 @@
+// A struct-level #[builder(build_fn(validate = "path"))] names a function that
+// `build` calls with a reference to the builder before assembling the struct:
+//
+//     fn validate(builder: &ServerBuilder) -> Result<(), String>
+//
+// An Err is returned from `build` as the ValidationError variant of the
+// builder's error type. Missing required fields are reported before the
+// validation runs.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(build_fn(validate = "Self::check_ports"))]
+pub struct Server {
+    port: u16,
+    admin_port: Option<u16>,
+}
+
+impl ServerBuilder {
+    fn check_ports(&self) -> Result<(), String> {
+        match (self.port, self.admin_port) {
+            (Some(port), Some(Some(admin_port))) if port == admin_port => {
+                Err(format!("port {} is used twice", port))
+            }
+            (Some(port), _) if port < 1024 => Err(format!("port {} is privileged", port)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Builder)]
+#[builder(typestate, build_fn(validate = "validate_range"))]
+pub struct Range {
+    start: u32,
+    end: u32,
+}
+
+fn validate_range(builder: &RangeBuilder<u32, u32>) -> Result<(), String> {
+    if builder.start <= builder.end {
+        Ok(())
+    } else {
+        Err("start is after end".to_owned())
+    }
+}
+
+fn main() {
+    let server = Server::builder().port(8080).admin_port(8081).build().unwrap();
+    assert_eq!(server.port, 8080);
+
+    let err = Server::builder().port(8080).admin_port(8080).build().err().unwrap();
+    assert!(matches!(err, ServerBuilderError::ValidationError(ref message) if message == "port 8080 is used twice"));
+    assert_eq!(err.to_string(), "port 8080 is used twice");
+
+    let mut builder = Server::builder();
+    builder.port(80);
+    assert_eq!(builder.build().err().unwrap().to_string(), "port 80 is privileged");
+    assert_eq!(builder.port(443).build().err().unwrap().to_string(), "port 443 is privileged");
+
+    let err = Server::builder().admin_port(9000).build().err().unwrap();
+    assert!(matches!(err, ServerBuilderError::UninitializedFields(_)));
+
+    let range = Range::builder().start(1).end(5).build().unwrap();
+    assert_eq!((range.start, range.end), (1, 5));
+
+    let err = Range::builder().start(5).end(1).build().err().unwrap();
+    assert!(matches!(err, RangeBuilderError::ValidationError(_)));
+}
@@ -0,0 +1,75 @@
+// #[builder(name = "...")] picks the name of the builder type instead of
+// `XBuilder`, for example when that name is already taken, and its error type
+// is named after it. On enums the name goes on each variant. The build
+// function can be renamed with #[builder(build_fn(name = "..."))] and its
+// visibility set with `vis`, where an empty `vis` makes it private so that a
+// handwritten wrapper can be exposed in its place.
+
+use derive_builder::Builder;
+
+pub struct ConfigBuilder;
+
+#[derive(Builder)]
+#[builder(name = "ConfigFactory", build_fn(name = "finish"))]
+pub struct Config {
+    path: String,
+}
+
+#[derive(Builder)]
+pub enum Shape {
+    #[builder(name = "CircleSpec")]
+    Circle { radius: f64 },
+    Square { side: f64 },
+}
+
+mod net {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    #[builder(build_fn(name = "build_unchecked", vis = ""))]
+    pub struct Address {
+        host: String,
+        port: u16,
+    }
+
+    impl AddressBuilder {
+        pub fn build(&mut self) -> Result<Address, AddressBuilderError> {
+            self.build_unchecked()
+        }
+    }
+
+    impl Address {
+        pub fn port(&self) -> u16 {
+            self.port
+        }
+    }
+
+    pub fn localhost() -> AddressBuilder {
+        let mut builder = Address::builder();
+        builder.host("localhost".to_owned());
+        builder
+    }
+
+    pub fn with_port(builder: &mut AddressBuilder, port: u16) -> &mut AddressBuilder {
+        builder.port(port)
+    }
+}
+
+fn main() {
+    let _ = ConfigBuilder;
+
+    let mut factory: ConfigFactory = Config::builder();
+    let config = factory.path("/etc/app.toml".to_owned()).finish().unwrap();
+    assert_eq!(config.path, "/etc/app.toml");
+
+    let err: ConfigFactoryError = Config::builder().finish().err().unwrap();
+    assert_eq!(err.to_string(), "field `path` is not set");
+
+    let mut circle: CircleSpec = Shape::build_circle();
+    assert!(matches!(circle.radius(1.0).build().unwrap(), Shape::Circle { .. }));
+    let _: ShapeSquareBuilder = Shape::build_square();
+
+    let mut address = net::localhost();
+    let address = net::with_port(&mut address, 80).build().unwrap();
+    assert_eq!(address.port(), 80);
+}
@@ -0,0 +1,16 @@
+// A build function made private with `build_fn(vis = "")` can't be called from
+// outside of the module defining the struct.
+
+mod net {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    #[builder(build_fn(vis = ""))]
+    pub struct Address {
+        host: String,
+    }
+}
+
+fn main() {
+    let _ = net::Address::builder().build();
+}
@@ -0,0 +1,80 @@
+// The builder, its error type, `builder()` and `build` are as visible as the
+// input, and every setter is as visible as the field it sets. A field of an
+// enum variant has no visibility of its own, so its setter follows the enum.
+// #[builder(vis = "...")] overrides the visibility of the builder on the
+// struct, and that of a single setter on a field.
+
+mod shop {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    pub struct Order {
+        pub item: String,
+        #[builder(vis = "pub")]
+        quantity: u32,
+        // only settable from within `shop`.
+        discount: Option<u8>,
+    }
+
+    impl Order {
+        pub fn quantity(&self) -> u32 {
+            self.quantity
+        }
+
+        pub fn discount(&self) -> Option<u8> {
+            self.discount
+        }
+    }
+
+    pub fn discounted() -> OrderBuilder {
+        let mut builder = Order::builder();
+        builder.discount(10);
+        builder
+    }
+
+    #[derive(Builder)]
+    pub enum Payment {
+        Card { number: String },
+    }
+
+    #[derive(Builder)]
+    #[builder(vis = "pub(crate)")]
+    pub struct Receipt {
+        pub total: u32,
+    }
+
+    // private struct, private builder.
+    #[derive(Builder)]
+    struct Ledger {
+        entries: Vec<u32>,
+    }
+
+    pub fn ledger_len() -> usize {
+        Ledger::builder().entries(vec![1, 2]).build().unwrap().entries.len()
+    }
+}
+
+fn main() {
+    let order = shop::Order::builder()
+        .item("tea".to_owned())
+        .quantity(2)
+        .build()
+        .unwrap();
+    assert_eq!(order.item, "tea");
+    assert_eq!(order.quantity(), 2);
+    assert_eq!(order.discount(), None);
+
+    let order = shop::discounted().item("coffee".to_owned()).quantity(1).build().unwrap();
+    assert_eq!(order.discount(), Some(10));
+
+    let err: shop::OrderBuilderError = shop::Order::builder().build().err().unwrap();
+    assert_eq!(err.to_string(), "fields `item`, `quantity` are not set");
+
+    let payment = shop::Payment::build_card().number("4242".to_owned()).build().unwrap();
+    assert!(matches!(payment, shop::Payment::Card { .. }));
+
+    let receipt = shop::Receipt::builder().total(3).build().unwrap();
+    assert_eq!(receipt.total, 3);
+
+    assert_eq!(shop::ledger_len(), 2);
+}
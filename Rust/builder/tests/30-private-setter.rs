@@ -0,0 +1,16 @@
+// Setters of private fields are private, so the builder of a public struct
+// can't set them from outside of the module defining it.
+
+mod shop {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    pub struct Order {
+        pub item: String,
+        discount: Option<u8>,
+    }
+}
+
+fn main() {
+    let _ = shop::Order::builder().item("tea".to_owned()).discount(10).build();
+}
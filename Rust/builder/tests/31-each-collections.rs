@@ -0,0 +1,74 @@
+// #[builder(each = "...")] works on any collection that is `Default` and can
+// be extended with its items, not just on Vec. The one-at-a-time setter of a
+// map takes a key and a value. The item type is the first type argument of
+// the collection, and can be given with `each(name = "...", item = "...")`
+// for a collection that has none, like a String extended with chars.
+
+use derive_builder::Builder;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+#[derive(Default)]
+pub struct Tags(Vec<String>);
+
+impl Extend<String> for Tags {
+    fn extend<I: IntoIterator<Item = String>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
+
+#[derive(Builder)]
+pub struct Request {
+    #[builder(each = "header")]
+    headers: HashMap<String, String>,
+    #[builder(each = "param", setter(into))]
+    params: BTreeMap<String, String>,
+    #[builder(each = "flag")]
+    flags: HashSet<u8>,
+    #[builder(each = "path")]
+    paths: BTreeSet<&'static str>,
+    #[builder(each = "step")]
+    steps: VecDeque<u32>,
+    #[builder(each(name = "tag", item = "String"))]
+    tags: Tags,
+    #[builder(each(name = "letter", item = "char"))]
+    letters: String,
+    #[builder(each = "retries")]
+    retries: std::collections::LinkedList<u8>,
+}
+
+fn main() {
+    let request = Request::builder()
+        .header("Accept".to_owned(), "text/html".to_owned())
+        .header("Host".to_owned(), "example.com".to_owned())
+        .param("q", "rust")
+        .flag(1)
+        .flag(1)
+        .path("/b")
+        .path("/a")
+        .step(1)
+        .step(2)
+        .tag("new".to_owned())
+        .letter('o')
+        .letter('k')
+        .build()
+        .unwrap();
+
+    assert_eq!(request.headers.len(), 2);
+    assert_eq!(request.headers["Host"], "example.com");
+    assert_eq!(request.params["q"], "rust");
+    assert_eq!(request.flags.len(), 1);
+    assert_eq!(request.paths.iter().copied().collect::<Vec<_>>(), ["/a", "/b"]);
+    assert_eq!(request.steps, [1, 2]);
+    assert_eq!(request.tags.0, ["new"]);
+    assert_eq!(request.letters, "ok");
+    assert!(request.retries.is_empty());
+
+    // the all-at-once setter replaces what was added so far.
+    let request = Request::builder()
+        .step(1)
+        .steps(VecDeque::from([5]))
+        .step(6)
+        .build()
+        .unwrap();
+    assert_eq!(request.steps, [5, 6]);
+}
@@ -0,0 +1,34 @@
+// Option and Vec are recognised however they are spelled, by their own name or
+// through the `std`, `core` and `alloc` crates, with or without a leading
+// `::`. A fully qualified Option field is optional like a bare one, and
+// `each` finds the item type of a fully qualified Vec.
+
+extern crate alloc;
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: std::vec::Vec<String>,
+    #[builder(each = "env")]
+    env: ::alloc::vec::Vec<String>,
+    current_dir: std::option::Option<String>,
+    niceness: ::core::option::Option<i8>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .env("RUST_LOG=debug".to_owned())
+        .niceness(10)
+        .build()
+        .unwrap();
+
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.env, vec!["RUST_LOG=debug"]);
+    assert_eq!(command.current_dir, None);
+    assert_eq!(command.niceness, Some(10));
+}
@@ -0,0 +1,15 @@
+// The one-at-a-time setter of `each` takes the item type of the collection,
+// which is looked up in its type arguments. A field without any, like this
+// `String` or a field that isn't a collection at all, needs the item type
+// given with `each(name = "...", item = "...")`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "letter")]
+    flags: String,
+}
+
+fn main() {}
@@ -0,0 +1,49 @@
+// Whether a field is optional is decided by its type looking like an Option,
+// which goes wrong for an alias of one. #[builder(optional)] lets a field of
+// any type be left unset, its setter taking the whole type and `build`
+// falling back to its Default. #[builder(required)] makes an Option field one
+// that must be set, its setter taking the whole Option so that `None` can be
+// given explicitly.
+
+use derive_builder::Builder;
+
+type MaybePort = Option<u16>;
+
+#[derive(Builder)]
+pub struct Server {
+    host: String,
+    #[builder(optional)]
+    port: MaybePort,
+    #[builder(optional)]
+    workers: u32,
+    #[builder(required)]
+    proxy: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Client {
+    #[builder(required)]
+    timeout: Option<u64>,
+    #[builder(optional)]
+    retries: u8,
+}
+
+fn main() {
+    let server = Server::builder()
+        .host("localhost".to_owned())
+        .port(Some(8080))
+        .proxy(None)
+        .build()
+        .unwrap();
+    assert_eq!(server.port, Some(8080));
+    assert_eq!(server.workers, 0);
+    assert_eq!(server.proxy, None);
+
+    let err = Server::builder().host("localhost".to_owned()).build().err().unwrap();
+    assert_eq!(err.to_string(), "field `proxy` is not set");
+
+    let client = Client::builder().timeout(Some(30)).build();
+    assert_eq!(client.timeout, Some(30));
+    assert_eq!(client.retries, 0);
+}
@@ -0,0 +1,12 @@
+// A union has no way to tell which of its fields is in use, so there's no
+// builder for it. Deriving one is an error pointing at the `union` keyword.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub union Number {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+// The name of the one-at-a-time setter is given as a string literal, anything
+// else is pointed out instead of crashing the derive.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = arg)]
+    args: Vec<String>,
+}
+
+fn main() {}
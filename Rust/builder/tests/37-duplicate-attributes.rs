@@ -0,0 +1,14 @@
+// Keys of several #[builder(...)] attributes on a field are merged, but every
+// key can only be given once. A repeated one is pointed out.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    #[builder(each = "argument")]
+    args: Vec<String>,
+}
+
+fn main() {}
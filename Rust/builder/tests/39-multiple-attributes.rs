@@ -0,0 +1,34 @@
+// A field can have several keys in one #[builder(...)] attribute, separated by
+// commas, and several attributes whose keys are merged.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(setter(into), name = "program")]
+    executable: String,
+    #[builder(each = "arg", setter(into))]
+    #[builder(default = "vec![\"--help\".to_owned()]")]
+    args: Vec<String>,
+    #[builder(vis = "pub")]
+    #[builder(try_setter)]
+    #[builder(default = "3")]
+    retries: u8,
+}
+
+fn main() {
+    let command = Command::builder().program("cargo").build().unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["--help"]);
+    assert_eq!(command.retries, 3);
+
+    let command = Command::builder()
+        .program("cargo")
+        .arg("build")
+        .try_retries(5u32)
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.retries, 5);
+}
@@ -0,0 +1,14 @@
+// Keys that contradict each other can't be given on the same field, whether
+// in one attribute or spread over several. The later one is pointed out.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(required)]
+    #[builder(default)]
+    current_dir: Option<String>,
+}
+
+fn main() {}
@@ -0,0 +1,62 @@
+// A builder can be made from an existing value, with every field set from it,
+// to build a copy of it with a few fields changed. `From<X>` gives one by
+// consuming the value, `X::to_builder` by cloning its fields, which it needs
+// to be Clone only when it is called. Skipped fields are left to `build`.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(skip, default = "1")]
+    runs: u32,
+}
+
+// no `to_builder` for it, but still a `From`.
+pub struct Handle;
+
+#[derive(Builder)]
+pub struct Process {
+    pid: u32,
+    handle: Handle,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Pair<T> {
+    left: T,
+    right: T,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .build()
+        .unwrap();
+
+    let release = command.to_builder().arg("--release".to_owned()).build().unwrap();
+    assert_eq!(release.args, vec!["build", "--release"]);
+    assert_eq!(release.executable, "cargo");
+
+    let mut builder = CommandBuilder::from(command);
+    let check = builder
+        .args(vec!["check".to_owned()])
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(check.args, vec!["check"]);
+    assert_eq!(check.current_dir.as_deref(), Some(".."));
+    assert_eq!(check.runs, 1);
+
+    let process = Process::builder().pid(1).handle(Handle).build().unwrap();
+    let process = ProcessBuilder::from(process).pid(2).build().unwrap();
+    assert_eq!(process.pid, 2);
+
+    let pair = Pair::builder().left(1).right(2).build();
+    let swapped = pair.to_builder().left(pair.right).right(pair.left).build();
+    assert_eq!((swapped.left, swapped.right), (2, 1));
+}
@@ -0,0 +1,53 @@
+// The builder can derive traits with #[builder(derive(...))] and be given
+// attributes with #[builder(struct_attr(...))], to be printed, compared or
+// kept around as a template. #[builder(setter_attr(...))] puts attributes on
+// every setter, or on the setters of one field when given there, and
+// #[builder(field_attr(...))] on a field of the builder. An immutable builder
+// derives Clone anyway, asking for it again is fine.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(derive(Debug, Clone, PartialEq, Default), struct_attr(must_use))]
+#[builder(setter_attr(inline))]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg", setter_attr(must_use))]
+    args: Vec<String>,
+    #[builder(field_attr(doc = "Directory to run in."))]
+    current_dir: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "immutable", derive(std::clone::Clone, Debug))]
+pub struct Query {
+    table: String,
+}
+
+#[derive(Builder)]
+#[builder(typestate, derive(Debug))]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn main() {
+    let mut template = CommandBuilder::default();
+    template.executable("cargo".to_owned());
+    let _ = template.arg("build".to_owned());
+
+    let mut release = template.clone();
+    let _ = release.arg("--release".to_owned());
+    assert_ne!(template, release);
+    assert!(format!("{:?}", template).starts_with("CommandBuilder"));
+
+    let command = release.build().unwrap();
+    assert_eq!(command.args, vec!["build", "--release"]);
+
+    let query = Query::builder().table("users".to_owned());
+    let _ = format!("{:?}", query.clone());
+
+    let point = Point::builder().x(1);
+    assert!(format!("{:?}", point).contains("x: 1"));
+    assert_eq!(point.y(2).build().y, 2);
+}
@@ -0,0 +1,81 @@
+// With #[builder(no_std)] the generated code uses `core` and `alloc` instead
+// of `std`, for crates that can't link std. The error type implements
+// `core::error::Error`.
+//
+// trybuild runs the test as a binary, which needs std for its `main`, so std
+// is still linked here, under another name. That shows the generated code
+// never names `std`, not that the crate would build for a target without std.
+//
+// Paths the user writes in field types, defaults and `validate` are left as
+// they are.
+
+#![no_std]
+
+extern crate alloc;
+extern crate std as host;
+
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(no_std, build_fn(validate = "check_pins"))]
+pub struct Device {
+    name: String,
+    #[builder(each = "pin")]
+    pins: Vec<u8>,
+    #[builder(each = "register")]
+    registers: BTreeMap<u16, u32>,
+    baud_rate: Option<u32>,
+    #[builder(default = "8", try_setter)]
+    data_bits: u8,
+    #[builder(default = "alloc::sync::Arc::new(core::cell::Cell::new(0))")]
+    counter: alloc::sync::Arc<core::cell::Cell<u32>>,
+    #[builder(each = "alias")]
+    aliases: alloc::collections::BTreeSet<alloc::string::String>,
+    timeout: core::option::Option<core::time::Duration>,
+}
+
+fn check_pins(builder: &DeviceBuilder) -> Result<(), String> {
+    match &builder.pins {
+        Some(pins) if pins.len() > 4 => Err("too many pins".to_owned()),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Builder)]
+#[builder(no_std, typestate)]
+pub struct Frame<T> {
+    header: T,
+    payload: Vec<T>,
+}
+
+fn assert_error<E: core::error::Error>(_: &E) {}
+
+fn main() {
+    let device = Device::builder()
+        .name("uart0".to_owned())
+        .pin(1)
+        .pin(2)
+        .register(0x10, 0xff)
+        .try_data_bits(7u32)
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(device.pins, [1, 2]);
+    assert_eq!(device.registers[&0x10], 0xff);
+    assert_eq!(device.baud_rate, None);
+    assert_eq!(device.data_bits, 7);
+    assert_eq!(device.counter.get(), 0);
+    assert!(device.aliases.is_empty());
+    assert_eq!(device.timeout, None);
+
+    let err = Device::builder().build().err().unwrap();
+    assert_error(&err);
+    assert_eq!(err.to_string(), "field `name` is not set");
+
+    let frame = Frame::builder().header(1).payload([2, 3].to_vec()).build();
+    assert_eq!(frame.header + frame.payload.len(), 3);
+}
@@ -0,0 +1,55 @@
+// Builders of the same type can be layered, for configuration that comes from
+// defaults, a file and the command line. `merge` takes every field that is
+// set in another builder, `or` only fills in the fields that aren't set yet.
+// The collection of an `each` field is replaced as a whole by `merge`, or has
+// the other items added to it with #[builder(merge = "append")].
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+pub struct Config {
+    host: String,
+    port: u16,
+    user: Option<String>,
+    #[builder(each = "include", merge = "append")]
+    includes: Vec<String>,
+    #[builder(each = "feature")]
+    features: Vec<String>,
+}
+
+fn main() {
+    let mut defaults = Config::builder();
+    defaults
+        .host("localhost".to_owned())
+        .port(80)
+        .include("base.toml".to_owned())
+        .feature("default".to_owned());
+
+    let mut file = Config::builder();
+    file.port(8080)
+        .include("app.toml".to_owned())
+        .feature("tls".to_owned());
+
+    let mut cli = Config::builder();
+    cli.user("admin".to_owned());
+
+    let config = defaults.merge(file).merge(cli).build().unwrap();
+    assert_eq!(config.host, "localhost");
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.user.as_deref(), Some("admin"));
+    assert_eq!(config.includes, vec!["base.toml", "app.toml"]);
+    assert_eq!(config.features, vec!["tls"]);
+
+    let mut cli = Config::builder();
+    cli.port(443);
+    let mut fallback = Config::builder();
+    fallback
+        .host("example.com".to_owned())
+        .port(80)
+        .feature("default".to_owned());
+    let config = cli.or(fallback).build().unwrap();
+    assert_eq!(config.host, "example.com");
+    assert_eq!(config.port, 443);
+    assert_eq!(config.user, None);
+    assert_eq!(config.features, vec!["default"]);
+}
@@ -0,0 +1,12 @@
+// `merge = "..."` only tells how the collection of an `each` field is merged,
+// every other field is replaced when it is set in the other builder.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Config {
+    #[builder(merge = "append")]
+    includes: Vec<String>,
+}
+
+fn main() {}
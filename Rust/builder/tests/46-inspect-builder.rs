@@ -0,0 +1,40 @@
+// What has been set on a builder so far can be looked at before building, for
+// example to show what is still needed. `get_x` returns a reference to the
+// value of field `x` if it is set, `is_x_set` tells whether it is, and
+// `missing_fields` lists the required fields that aren't set yet, by the
+// same names as the error of `build`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(default = "1")]
+    runs: u32,
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    assert_eq!(builder.missing_fields(), ["executable", "env"]);
+    assert!(!builder.is_executable_set());
+    assert_eq!(builder.get_executable(), None);
+    assert_eq!(builder.get_args(), None);
+
+    builder.executable("cargo".to_owned()).arg("build".to_owned());
+    assert_eq!(builder.missing_fields(), ["env"]);
+    assert!(builder.is_executable_set());
+    assert_eq!(builder.get_executable().map(String::as_str), Some("cargo"));
+    assert_eq!(builder.get_args().map(Vec::len), Some(1));
+
+    builder.current_dir("..".to_owned()).runs(3);
+    assert_eq!(builder.get_current_dir().map(String::as_str), Some(".."));
+    assert_eq!(builder.get_runs(), Some(&3));
+
+    builder.env(vec![]);
+    assert!(builder.missing_fields().is_empty());
+    assert!(builder.build().is_ok());
+}
@@ -0,0 +1,41 @@
+// A crate that is `no_std` unless a cargo feature links std usually spells
+// the std types it then uses as `std::...`. With #[builder(no_std)] only the
+// paths of the generated code go to `core` and `alloc`, the ones in field
+// types, defaults and where clauses are kept as written.
+
+#![no_std]
+
+extern crate alloc;
+extern crate std;
+
+use derive_builder::Builder;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Builder)]
+#[builder(no_std)]
+pub struct Cache<T>
+where
+    T: std::clone::Clone,
+{
+    #[builder(default = "std::sync::Arc::new(1)")]
+    shared: std::sync::Arc<u8>,
+    label: std::borrow::Cow<'static, str>,
+    #[builder(each = "entry")]
+    entries: std::collections::HashMap<u8, T>,
+}
+
+fn main() {
+    let cache = Cache::builder()
+        .label(Cow::Borrowed("hot"))
+        .entry(1, "one")
+        .build()
+        .unwrap();
+
+    let shared: Arc<u8> = cache.shared;
+    let entries: HashMap<u8, &str> = cache.entries;
+    assert_eq!(*shared, 1);
+    assert_eq!(cache.label, "hot");
+    assert_eq!(entries[&1], "one");
+}
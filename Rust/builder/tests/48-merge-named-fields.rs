@@ -0,0 +1,23 @@
+// A field named like a method the builder has besides its setters keeps its
+// setter, and the builder goes without that method. Here `merge` and `or` are
+// settings of their own rather than ways to combine builders.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Join {
+    pub merge: bool,
+    pub or: u8,
+    pub table: String,
+}
+
+fn main() {
+    let join = Join::builder()
+        .merge(true)
+        .or(2)
+        .table("users".to_owned())
+        .build()
+        .unwrap();
+    assert!(join.merge);
+    assert_eq!(join.or, 2);
+}
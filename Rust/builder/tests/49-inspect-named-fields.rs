@@ -0,0 +1,44 @@
+// Getters are named after the field as written, `get_type` for a raw
+// `r#type`, and only a tuple struct's fields go by their index, `get_0`. The
+// builder goes without a getter, `is_x_set` or `missing_fields` whose name
+// is already taken by a setter.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Token {
+    pub r#type: String,
+    pub a: u8,
+    pub _a: u8,
+    pub name: String,
+    pub get_name: bool,
+    pub missing_fields: Vec<String>,
+}
+
+#[derive(Builder)]
+pub struct Span(usize, usize);
+
+fn main() {
+    let mut builder = Token::builder();
+    assert_eq!(builder.get_type(), None);
+    builder.r#type("ident".to_owned()).a(1)._a(2).name("x".to_owned());
+    assert_eq!(builder.get_type().map(String::as_str), Some("ident"));
+    assert_eq!(builder.get_a(), Some(&1));
+    assert_eq!(builder.get__a(), Some(&2));
+    assert!(builder.is_name_set());
+
+    // `get_name` and `missing_fields` are setters here.
+    builder.get_name(true).missing_fields(vec![]);
+    let token = builder.build().unwrap();
+    assert_eq!(token.r#type, "ident");
+    assert!(token.get_name);
+
+    let err = Token::builder().build().err().unwrap();
+    assert!(err.to_string().starts_with("fields `type`, `a`"));
+
+    let mut span = Span::builder();
+    span._0(1);
+    assert_eq!(span.get_0(), Some(&1));
+    assert!(!span.is_1_set());
+    assert_eq!(span.missing_fields(), ["_1"]);
+}
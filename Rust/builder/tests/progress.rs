@@ -10,4 +10,43 @@ fn tests() {
     t.pass("tests/07-repeated-field.rs");
     t.compile_fail("tests/08-unrecognized-attribute.rs");
     t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-tuple-struct.rs");
+    t.pass("tests/11-enum-variants.rs");
+    t.pass("tests/12-generics.rs");
+    t.pass("tests/13-typestate.rs");
+    t.compile_fail("tests/14-typestate-missing-field.rs");
+    t.pass("tests/15-builder-error.rs");
+    t.pass("tests/16-all-missing-fields.rs");
+    t.pass("tests/17-field-defaults.rs");
+    t.pass("tests/18-setter-into.rs");
+    t.pass("tests/19-try-setter.rs");
+    t.pass("tests/20-skip-field.rs");
+    t.compile_fail("tests/21-skip-field-no-setter.rs");
+    t.pass("tests/22-builder-patterns.rs");
+    t.compile_fail("tests/23-typestate-pattern.rs");
+    t.pass("tests/24-non-clone-fields.rs");
+    t.compile_fail("tests/25-clone-owned-builder.rs");
+    t.pass("tests/26-validate.rs");
+    t.pass("tests/27-custom-names.rs");
+    t.compile_fail("tests/28-private-build-fn.rs");
+    t.pass("tests/29-visibility.rs");
+    t.compile_fail("tests/30-private-setter.rs");
+    t.pass("tests/31-each-collections.rs");
+    t.pass("tests/32-qualified-paths.rs");
+    t.compile_fail("tests/33-each-unknown-item.rs");
+    t.pass("tests/34-optional-required.rs");
+    t.compile_fail("tests/35-union.rs");
+    t.compile_fail("tests/36-each-not-string.rs");
+    t.compile_fail("tests/37-duplicate-attributes.rs");
+    t.pass("tests/39-multiple-attributes.rs");
+    t.compile_fail("tests/40-conflicting-attributes.rs");
+    t.pass("tests/41-to-builder.rs");
+    t.pass("tests/42-builder-attributes.rs");
+    t.pass("tests/43-no-std.rs");
+    t.pass("tests/44-merge.rs");
+    t.compile_fail("tests/45-merge-without-each.rs");
+    t.pass("tests/46-inspect-builder.rs");
+    t.pass("tests/47-no-std-user-paths.rs");
+    t.pass("tests/48-merge-named-fields.rs");
+    t.pass("tests/49-inspect-named-fields.rs");
 }